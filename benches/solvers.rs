@@ -0,0 +1,48 @@
+// Compares the solver backends on generated puzzles: cargo bench --bench solvers
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use sudoku::sudoku::Sudoku;
+use sudoku::sudoku_bitboard::BitboardSolver;
+use sudoku::sudoku_dlx::DlxSolver;
+use sudoku::sudoku_factory::{SudokuFactory, Symmetry};
+use sudoku::sudoku_solver::{BacktrackingSolver, SudokuSolver};
+
+
+const N_PUZZLES: usize = 100;
+
+
+fn bench(name: &str, solver: &dyn SudokuSolver<9, 9>, puzzles: &[Sudoku<9, 9>])
+{
+    let start = Instant::now();
+
+    for puzzle in puzzles {
+        // counting up to two is what the factory does for every removed given
+        assert_eq!(puzzle.count_solutions_with(solver, 2), 1);
+    }
+
+    let elapsed = start.elapsed();
+    let per_puzzle = elapsed / puzzles.len() as u32;
+
+    println!("{:<14} {:>10.2?} total {:>10.2?} per puzzle", name, elapsed, per_puzzle);
+}
+
+
+fn main()
+{
+    let mut factory = SudokuFactory::<9, 9>::new(Box::new(StdRng::seed_from_u64(42)));
+
+    let start = Instant::now();
+    let puzzles: Vec<Sudoku<9, 9>> = (0..N_PUZZLES)
+        .map(|_| factory.generate_puzzle(0, Symmetry::None).puzzle)
+        .collect();
+    let generated: Duration = start.elapsed();
+
+    println!("generated {} puzzles in {:.2?}", N_PUZZLES, generated);
+
+    bench("backtracking", &BacktrackingSolver, &puzzles);
+    bench("dlx", &DlxSolver, &puzzles);
+    bench("bitboard", &BitboardSolver, &puzzles);
+}
@@ -1,9 +1,28 @@
 pub mod sudoku;
+pub mod sudoku_error;
+pub mod sudoku_cell;
 pub mod sudoku_iterator;
+pub mod sudoku_candidate_storage;
+pub mod sudoku_candidates;
+pub mod sudoku_geometry;
 pub mod sudoku_fmt;
 pub mod sudoku_pencil_notes;
 pub mod sudoku_pencil_notes_fmt;
+pub mod sudoku_pencil_notes_fish;
+pub mod sudoku_pencil_notes_wings;
+pub mod sudoku_pencil_notes_chains;
+pub mod sudoku_pencil_notes_aic;
+pub mod sudoku_pencil_notes_uniqueness;
+pub mod sudoku_pencil_notes_als;
 pub mod sudoku_factory;
+pub mod sudoku_solver;
+pub mod sudoku_dlx;
+pub mod sudoku_bitboard;
+pub mod sudoku_observer;
+pub mod sudoku_logical_solver;
+pub mod sudoku_rating;
+pub mod sudoku_batch;
+pub mod sudoku_game;
 
 /* 
 use sudoku::Sudoku;
@@ -1,36 +1,48 @@
+use std::collections::HashSet;
+
 use eframe::egui;
 
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 
+use sudoku::sudoku::Sudoku;
 use sudoku::sudoku_pencil_notes::{PencilNotes, RandomBit};
 
-use sudoku::sudoku_pencil_notes_fmt::*;
-
 // --------------------------
 
 struct SudokuApp {
     notes: PencilNotes<9, 9>,
     random_bit: RandomBit,
+    // solved cells that clash with another one in a row, column or block,
+    // only recomputed when a click changes the notes
+    conflicting: HashSet<(usize, usize)>,
 }
 
 impl SudokuApp {
     fn new(_cc: &eframe::CreationContext<'_>) -> Self {
         
+        let notes = PencilNotes::new();
+
         Self {
-            notes: PencilNotes::new(),
+            conflicting: Self::find_conflicts(&notes),
+            notes,
             random_bit: RandomBit::new(Box::new(StdRng::seed_from_u64(42))),
         }
     }
 
-    fn get_possible_numbers(mask: u32) -> Vec<u8> {
-        let mut numbers = Vec::new();
-        for i in 0..9 {
-            if (mask >> i) & 1 == 1 {
-                numbers.push((i + 1) as u8);
+    fn find_conflicts(notes: &PencilNotes<9, 9>) -> HashSet<(usize, usize)> {
+
+        let mut solved_board = [[0; 9]; 9];
+        for (row, numbers) in solved_board.iter_mut().enumerate() {
+            for (col, number) in numbers.iter_mut().enumerate() {
+                *number = notes.get_possibility(row, col).unwrap_or(0);
             }
         }
-        numbers
+
+        Sudoku::<9, 9>::new(solved_board).conflicts()
+            .into_iter()
+            .flat_map(|conflict| conflict.cells)
+            .collect()
     }
 }
 
@@ -44,7 +56,7 @@ impl eframe::App for SudokuApp {
             // Define grid styling
             let cell_size = 60.0; // Size of one square cell
             let spacing = 0.0;    // No gap between cells, we draw borders manually
-            
+
             // Create a Grid layout
             egui::Grid::new("sudoku_grid")
                 .spacing(egui::vec2(spacing, spacing))
@@ -59,20 +71,20 @@ impl eframe::App for SudokuApp {
                             );
 
                             // 2. DATA LOGIC
-                            let mask = self.notes.possibilities[row][col];
-                            let possibilities = Self::get_possible_numbers(mask);
-                            let is_solved = possibilities.len() == 1;
+                            let possibilities = self.notes.get_possibilities(row, col);
+                            let solved = possibilities.single();
+                            let is_solved = solved.is_some();
 
                             // 3. INTERACTION
                             // If clicked, pick a random number from the possibilities
                             if response.clicked() && !is_solved {
                                 println!("Clicked!");
-                                if let Some(number) = self.random_bit.select_random_bit(mask) {
-                                    let number = number + 1;
-                                    self.notes.set_possibility(row, col, number);
+                                if let Some(number) = self.random_bit.select_random_number(possibilities) {
+                                    self.notes.try_set_possibility(row, col, number).expect("the number is one of the cell's candidates");
                                     self.notes.eliminate_possibility(row, col, number);
+                                    self.conflicting = Self::find_conflicts(&self.notes);
 
-                                    println!("Mask: 0b{:09b}, Selected {}, Pencil Notes are now:\n{}", mask, number, self.notes);
+                                    println!("Possibilities: {}, Selected {}, Pencil Notes are now:\n{}", possibilities, number, self.notes);
                                 }
                             }
 
@@ -81,7 +93,9 @@ impl eframe::App for SudokuApp {
                             let painter = ui.painter();
 
                             // A. Draw background and basic cell border
-                            let bg_color = if response.hovered() && !is_solved {
+                            let bg_color = if self.conflicting.contains(&(row, col)) {
+                                egui::Color32::from_rgb(110, 20, 20) // Highlight clashes
+                            } else if response.hovered() && !is_solved {
                                 egui::Color32::from_gray(40) // Highlight on hover
                             } else {
                                 egui::Color32::from_gray(20)
@@ -90,12 +104,12 @@ impl eframe::App for SudokuApp {
                             painter.rect_stroke(rect, 0.0, egui::Stroke::new(1.0, egui::Color32::from_gray(60)), egui::StrokeKind::Outside);
 
                             // B. Draw Content
-                            if is_solved {
+                            if let Some(number) = solved {
                                 // DRAW BIG NUMBER
                                 painter.text(
                                     rect.center(),
                                     egui::Align2::CENTER_CENTER,
-                                    format!("{}", possibilities[0]),
+                                    format!("{}", number),
                                     egui::FontId::proportional(32.0),
                                     egui::Color32::WHITE,
                                 );
@@ -105,7 +119,7 @@ impl eframe::App for SudokuApp {
                                 let sub_w = rect.width() / 3.0;
                                 let sub_h = rect.height() / 3.0;
 
-                                for &num in &possibilities {
+                                for num in possibilities {
                                     // Calculate x,y index (0-2) for the number (1-9)
                                     // 1->(0,0), 2->(1,0), 3->(2,0) ... 9->(2,2)
                                     let idx = num - 1;
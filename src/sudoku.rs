@@ -1,37 +1,100 @@
 
-use crate::sudoku_iterator::{SudokuIterator, SudokuIteratorMode};
+use crate::sudoku_cell::{CellPos, Digit};
+use crate::sudoku_error::SudokuError;
+use crate::sudoku_geometry::Geometry;
+use crate::sudoku_iterator::Unit;
+
+
+// Cells of a unit that hold the same number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict
+{
+    pub unit: Unit,
+    pub number: u32,
+    pub cells: Vec<(usize, usize)>,
+}
 
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct Sudoku<const N_ROWS: usize, const N_COLS: usize>
+pub struct Sudoku<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize = 3, const BOX_W: usize = 3>
 {
     pub board: [[u32; N_COLS]; N_ROWS],
 }
 
 
-impl<const N_ROWS: usize, const N_COLS: usize> Sudoku<N_ROWS, N_COLS> {
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize> Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W> {
 
     const CHECK_CONSTRAINTS: () = {
         // Constraint 1: Minimum Size
-        assert!(N_ROWS > 3, "N_ROWS must be greater than 3");
-        assert!(N_COLS > 3, "N_COLS must be greater than 3");
+        assert!(BOX_H > 1, "BOX_H must be greater than 1");
+        assert!(BOX_W > 1, "BOX_W must be greater than 1");
+
+        // Constraint 2: Box Dimensions
+        // every row, column and box holds each number once, so all of them have BOX_H * BOX_W cells
+        assert!(N_ROWS == BOX_H * BOX_W, "N_ROWS must be BOX_H * BOX_W");
+        assert!(N_COLS == BOX_H * BOX_W, "N_COLS must be BOX_H * BOX_W");
 
-        // Constraint 2: Integer Size
-        let block_h = N_ROWS / 3;
-        let block_w = N_COLS / 3;
-        let symbols_needed = block_h * block_w;
+        // Constraint 3: Integer Size
+        let symbols_needed = BOX_H * BOX_W;
 
+        // the board itself takes any number, but the candidates have to fit the widest storage
         assert!(
-            symbols_needed <= u32::BITS as usize, 
-            "The chosen type u32 is too small for this grid size!"
+            symbols_needed <= u128::BITS as usize,
+            "There is no candidate storage for this grid size!"
         );
     };
 
     pub fn new(board: [[u32; N_COLS]; N_ROWS]) -> Self
     {
-        let _ = Self::CHECK_CONSTRAINTS;
+        let () = Self::CHECK_CONSTRAINTS;
+
+        Sudoku { board }
+    }
+
+    // like new, but every entry has to be 0 (empty) or a number of the grid
+    pub fn try_new(board: [[u32; N_COLS]; N_ROWS]) -> Result<Self, SudokuError>
+    {
+        let sudoku = Self::new(board);
+        let max_number = sudoku.max_number();
+
+        for (r, row) in board.iter().enumerate() {
+            for (c, &value) in row.iter().enumerate() {
+                if value > max_number {
+                    return Err(SudokuError::InvalidEntry { row: r, col: c, value, max_number });
+                }
+            }
+        }
+
+        Ok(sudoku)
+    }
+
+    pub fn get(&self, pos: CellPos<N_ROWS, N_COLS, BOX_H, BOX_W>) -> Option<Digit<N_ROWS, N_COLS, BOX_H, BOX_W>>
+    {
+        Digit::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(self.board[pos.row()][pos.col()]).ok()
+    }
 
-        Sudoku { board: board.clone() }
+    pub fn set(&mut self, pos: CellPos<N_ROWS, N_COLS, BOX_H, BOX_W>, digit: Digit<N_ROWS, N_COLS, BOX_H, BOX_W>)
+    {
+        self.board[pos.row()][pos.col()] = digit.get();
+    }
+
+    pub fn clear(&mut self, pos: CellPos<N_ROWS, N_COLS, BOX_H, BOX_W>)
+    {
+        self.board[pos.row()][pos.col()] = 0;
+    }
+
+    // checks the coordinate and the number, 0 empties the cell
+    pub fn try_set(&mut self, row: usize, col: usize, value: u32) -> Result<(), SudokuError>
+    {
+        let pos = CellPos::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(row, col)?;
+
+        if value == 0 {
+            self.clear(pos);
+        } else {
+            self.set(pos, Digit::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(value)?);
+        }
+
+        Ok(())
     }
 
     pub fn reset(&mut self)
@@ -59,38 +122,50 @@ impl<const N_ROWS: usize, const N_COLS: usize> Sudoku<N_ROWS, N_COLS> {
     
     pub fn is_valid(&self) -> bool
     {
-        for r in 0..N_ROWS {
-            let row = self.board[r];
+        self.conflicts().is_empty()
+    }
 
-            for c in 0..N_COLS {
-                let cell_value = row[c];
-                if cell_value == 0
-                {
-                    continue;
-                }
+    // the first conflict as an error
+    pub fn validate(&self) -> Result<(), SudokuError>
+    {
+        match self.conflicts().into_iter().next() {
+            Some(Conflict { unit, number, cells }) => Err(SudokuError::Conflict { unit, number, cells }),
+            None => Ok(()),
+        }
+    }
 
-                for (c_r, c_c) in SudokuIterator::<N_ROWS, N_COLS>::new(r, c, SudokuIteratorMode::Affected)
-                {
-                    if c_r == r && c_c == c
-                    {
-                        continue;
-                    }
-
-                    let check_value = self.board[c_r][c_c];
-                    if check_value == cell_value
-                    {
-                        return false;
-                    }
+    // every group of cells sharing a number in a row, column or square.
+    // rows come first, then columns, then squares, numbers ascending within a unit.
+    // a cell clashing in several units shows up in each of them.
+    pub fn conflicts(&self) -> Vec<Conflict>
+    {
+        let mut conflicts = Vec::new();
+
+        for (unit, cells) in Geometry::of::<N_ROWS, N_COLS, BOX_H, BOX_W>().units() {
+
+            let mut entries: Vec<(u32, (usize, usize))> = cells.iter()
+                .map(|&(r, c)| (self.board[r][c], (r, c)))
+                .filter(|&(number, _)| number != 0)
+                .collect();
+            entries.sort_by_key(|&(number, _)| number);
+
+            for group in entries.chunk_by(|a, b| a.0 == b.0) {
+                if group.len() > 1 {
+                    conflicts.push(Conflict {
+                        unit,
+                        number: group[0].0,
+                        cells: group.iter().map(|&(_, cell)| cell).collect(),
+                    });
                 }
             }
         }
 
-        true
+        conflicts
     }
 
     pub fn max_number(&self) -> u32 {
 
-        (N_ROWS / 3 * N_COLS / 3) as u32
+        (BOX_H * BOX_W) as u32
     }
 
 }
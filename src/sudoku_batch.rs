@@ -0,0 +1,120 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::sudoku::Sudoku;
+use crate::sudoku_candidate_storage::{DefaultStorage, Symbols};
+use crate::sudoku_factory::{Puzzle, SudokuFactory, Symmetry};
+use crate::sudoku_pencil_notes::RandomBit;
+use crate::sudoku_rating::{rate, Rating};
+
+
+// Solves, rates and generates many sudokus at once, spread over a number of threads.
+// Results always come back in the order of the input. Generation draws one seed per puzzle
+// from the given RandomBit up front, so the puzzles don't depend on the number of threads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Batch
+{
+    pub threads: usize,
+}
+
+
+impl Default for Batch {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+impl Batch {
+
+    // one thread per core
+    pub fn new() -> Self
+    {
+        let threads = thread::available_parallelism().map(|threads| threads.get()).unwrap_or(1);
+
+        Batch { threads }
+    }
+
+    pub fn with_threads(threads: usize) -> Self
+    {
+        Batch { threads: threads.max(1) }
+    }
+
+    // applies `f` to every item, with the threads picking the next open item until none are left
+    pub fn map<T: Sync, U: Send>(&self, items: &[T], f: impl Fn(&T) -> U + Sync) -> Vec<U>
+    {
+        let next = AtomicUsize::new(0);
+        let results: Mutex<Vec<(usize, U)>> = Mutex::new(Vec::with_capacity(items.len()));
+
+        thread::scope(|scope| {
+            for _ in 0..self.threads.min(items.len()) {
+                scope.spawn(|| {
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(index) else {
+                            break;
+                        };
+
+                        let result = f(item);
+                        results.lock().unwrap().push((index, result));
+                    }
+                });
+            }
+        });
+
+        let mut results = results.into_inner().unwrap();
+        results.sort_by_key(|&(index, _)| index);
+
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    pub fn solve<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize>(&self, puzzles: impl IntoIterator<Item = Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>>) -> Vec<Option<Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>>>
+    where
+        Symbols<N_ROWS>: DefaultStorage,
+    {
+        let puzzles: Vec<Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>> = puzzles.into_iter().collect();
+
+        self.map(&puzzles, |puzzle| puzzle.solve())
+    }
+
+    pub fn rate<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize>(&self, puzzles: impl IntoIterator<Item = Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>>) -> Vec<Rating>
+    where
+        Symbols<N_ROWS>: DefaultStorage,
+    {
+        let puzzles: Vec<Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>> = puzzles.into_iter().collect();
+
+        self.map(&puzzles, rate)
+    }
+
+    // `count` puzzles like SudokuFactory::generate_puzzle, each from a factory seeded by `random_bit`
+    pub fn generate<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize>(&self, count: usize, random_bit: &mut RandomBit, target_clues: usize, symmetry: Symmetry) -> Vec<Puzzle<N_ROWS, N_COLS, BOX_H, BOX_W>>
+    where
+        Symbols<N_ROWS>: DefaultStorage,
+    {
+        let seeds: Vec<u64> = (0..count).map(|_| random_bit.next_seed()).collect();
+
+        self.map(&seeds, |&seed| {
+            SudokuFactory::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(Box::new(StdRng::seed_from_u64(seed))).generate_puzzle(target_clues, symmetry)
+        })
+    }
+
+    // like `generate`, with every puzzle rated by the thread that generated it
+    pub fn generate_and_rate<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize>(&self, count: usize, random_bit: &mut RandomBit, target_clues: usize, symmetry: Symmetry) -> Vec<(Puzzle<N_ROWS, N_COLS, BOX_H, BOX_W>, Rating)>
+    where
+        Symbols<N_ROWS>: DefaultStorage,
+    {
+        let seeds: Vec<u64> = (0..count).map(|_| random_bit.next_seed()).collect();
+
+        self.map(&seeds, |&seed| {
+            let puzzle = SudokuFactory::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(Box::new(StdRng::seed_from_u64(seed))).generate_puzzle(target_clues, symmetry);
+            let rating = rate(&puzzle.puzzle);
+
+            (puzzle, rating)
+        })
+    }
+}
@@ -0,0 +1,268 @@
+use crate::sudoku::Sudoku;
+use crate::sudoku_solver::SudokuSolver;
+
+
+const N_CELLS: usize = 81;
+const ALL_NUMBERS: u16 = 0x1ff;
+
+
+// the cells of every unit: rows, then columns, then squares
+const UNITS: [[u8; 9]; 27] = build_units();
+
+// the 20 cells sharing a unit with every cell
+const PEERS: [[u8; 20]; N_CELLS] = build_peers();
+
+
+const fn build_units() -> [[u8; 9]; 27]
+{
+    let mut units = [[0u8; 9]; 27];
+
+    let mut i = 0;
+    while i < 9 {
+        let mut j = 0;
+        while j < 9 {
+            units[i][j] = (i * 9 + j) as u8;
+            units[9 + i][j] = (j * 9 + i) as u8;
+            units[18 + i][j] = (((i / 3) * 3 + j / 3) * 9 + (i % 3) * 3 + j % 3) as u8;
+            j += 1;
+        }
+        i += 1;
+    }
+
+    units
+}
+
+
+const fn build_peers() -> [[u8; 20]; N_CELLS]
+{
+    let mut peers = [[0u8; 20]; N_CELLS];
+
+    let mut cell = 0;
+    while cell < N_CELLS {
+
+        let (row, col) = (cell / 9, cell % 9);
+        let mut count = 0;
+
+        let mut other = 0;
+        while other < N_CELLS {
+
+            let (other_row, other_col) = (other / 9, other % 9);
+            let same_square = row / 3 == other_row / 3 && col / 3 == other_col / 3;
+
+            if other != cell && (row == other_row || col == other_col || same_square) {
+                peers[cell][count] = other as u8;
+                count += 1;
+            }
+            other += 1;
+        }
+        cell += 1;
+    }
+
+    peers
+}
+
+
+// A 9x9 board as one candidate mask per cell, cheap to copy for every guess.
+// Cells are numbered row * 9 + col.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bitboard
+{
+    pub candidates: [u16; N_CELLS],
+    // the placed numbers, 0 for open cells
+    pub values: [u8; N_CELLS],
+}
+
+
+impl Bitboard {
+
+    // None if the givens collide
+    pub fn new(sudoku: &Sudoku<9, 9>) -> Option<Self>
+    {
+        let mut board = Bitboard { candidates: [ALL_NUMBERS; N_CELLS], values: [0; N_CELLS] };
+
+        for cell in 0..N_CELLS {
+
+            let number = sudoku.board[cell / 9][cell % 9];
+            if number == 0 {
+                continue;
+            }
+
+            if number > 9 || board.candidates[cell] & (1 << (number - 1)) == 0 || !board.place(cell, number as u8) {
+                return None;
+            }
+        }
+
+        Some(board)
+    }
+
+    // returns false if a peer runs out of candidates
+    pub fn place(&mut self, cell: usize, number: u8) -> bool
+    {
+        let bit = 1u16 << (number - 1);
+
+        self.values[cell] = number;
+        self.candidates[cell] = bit;
+
+        for &peer in &PEERS[cell] {
+
+            let peer = peer as usize;
+            if self.candidates[peer] & bit != 0 {
+
+                self.candidates[peer] &= !bit;
+                if self.candidates[peer] == 0 {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    // places naked and hidden singles until nothing changes anymore.
+    // returns false as soon as a contradiction shows up.
+    pub fn propagate(&mut self) -> bool
+    {
+        loop {
+            let mut progress = false;
+
+            for cell in 0..N_CELLS {
+
+                let candidates = self.candidates[cell];
+                if self.values[cell] == 0 && candidates.is_power_of_two() {
+
+                    if !self.place(cell, candidates.trailing_zeros() as u8 + 1) {
+                        return false;
+                    }
+                    progress = true;
+                }
+            }
+
+            for unit in &UNITS {
+
+                // numbers seen at least once and at least twice in the unit
+                let mut once = 0u16;
+                let mut twice = 0u16;
+                for &cell in unit {
+                    let candidates = self.candidates[cell as usize];
+                    twice |= once & candidates;
+                    once |= candidates;
+                }
+
+                if once != ALL_NUMBERS {
+                    return false;
+                }
+
+                let mut hidden = once & !twice;
+                while hidden != 0 {
+
+                    let bit = hidden & hidden.wrapping_neg();
+                    hidden &= hidden - 1;
+
+                    let cell = unit.iter().map(|&cell| cell as usize).find(|&cell| self.candidates[cell] & bit != 0);
+
+                    // an earlier placement in this pass took the number away
+                    let Some(cell) = cell else {
+                        return false;
+                    };
+
+                    if self.values[cell] == 0 {
+                        if !self.place(cell, bit.trailing_zeros() as u8 + 1) {
+                            return false;
+                        }
+                        progress = true;
+                    }
+                }
+            }
+
+            if !progress {
+                return true;
+            }
+        }
+    }
+
+    // the open cell with the fewest candidates, None if the board is complete
+    pub fn open_cell(&self) -> Option<usize>
+    {
+        (0..N_CELLS)
+            .filter(|&cell| self.values[cell] == 0)
+            .min_by_key(|&cell| self.candidates[cell].count_ones())
+    }
+
+    pub fn to_sudoku(self) -> Sudoku<9, 9>
+    {
+        let mut board = [[0; 9]; 9];
+        for cell in 0..N_CELLS {
+            board[cell / 9][cell % 9] = self.values[cell] as u32;
+        }
+        Sudoku::new(board)
+    }
+}
+
+
+// depth first search with singles propagation, like `sudoku_solver::search` but on bitboards
+fn search(mut board: Bitboard, found: &mut dyn FnMut(&Sudoku<9, 9>) -> bool) -> bool
+{
+    if !board.propagate() {
+        return false;
+    }
+
+    let Some(cell) = board.open_cell() else {
+        return found(&board.to_sudoku());
+    };
+
+    let mut candidates = board.candidates[cell];
+    while candidates != 0 {
+
+        let number = candidates.trailing_zeros() as u8 + 1;
+        candidates &= candidates - 1;
+
+        let mut next = board;
+        if next.place(cell, number) && search(next, found) {
+            return true;
+        }
+    }
+
+    false
+}
+
+
+// A solver for 9x9 sudokus on precomputed peer tables and per cell candidate masks.
+// Much faster than the generic backtracking solver, for bulk solving and counting.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BitboardSolver;
+
+
+impl SudokuSolver<9, 9> for BitboardSolver {
+
+    fn solve(&self, sudoku: &Sudoku<9, 9>) -> Option<Sudoku<9, 9>>
+    {
+        let mut solution = None;
+
+        if let Some(board) = Bitboard::new(sudoku) {
+            search(board, &mut |sudoku| {
+                solution = Some(*sudoku);
+                true
+            });
+        }
+
+        solution
+    }
+
+    fn count_solutions(&self, sudoku: &Sudoku<9, 9>, limit: usize) -> usize
+    {
+        let mut count = 0;
+
+        if limit == 0 {
+            return count;
+        }
+
+        if let Some(board) = Bitboard::new(sudoku) {
+            search(board, &mut |_| {
+                count += 1;
+                count >= limit
+            });
+        }
+
+        count
+    }
+}
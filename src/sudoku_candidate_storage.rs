@@ -0,0 +1,119 @@
+use std::fmt::{Binary, Debug};
+use std::hash::Hash;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not};
+
+
+// The unsigned integer a set of candidates is kept in, one bit per number (bit = number - 1).
+// The smallest one with a bit for every number of the grid is the default (see DefaultStorage):
+// u16 for up to 16 numbers, u32 for up to 32, u64 for 36x36 and 49x49, u128 for up to 128 numbers.
+pub trait CandidateStorage:
+    Copy + Eq + Ord + Hash + Debug + Binary + Default + Send + Sync + 'static
+    + BitAnd<Output = Self> + BitOr<Output = Self> + Not<Output = Self> + BitAndAssign + BitOrAssign
+{
+    const BITS: u32;
+    const EMPTY: Self;
+
+    // only the bit at `index`
+    fn bit(index: u32) -> Self;
+
+    // the lowest `count` bits
+    fn lowest(count: u32) -> Self;
+
+    fn count_ones(self) -> u32;
+    fn trailing_zeros(self) -> u32;
+
+    // the same bits without the lowest one
+    fn without_lowest(self) -> Self;
+
+    fn is_empty(self) -> bool
+    {
+        self == Self::EMPTY
+    }
+
+    fn has_bit(self, index: u32) -> bool
+    {
+        self & Self::bit(index) != Self::EMPTY
+    }
+}
+
+
+macro_rules! candidate_storage {
+    ($($storage:ty),*) => {
+        $(
+            impl CandidateStorage for $storage {
+
+                const BITS: u32 = <$storage>::BITS;
+                const EMPTY: Self = 0;
+
+                fn bit(index: u32) -> Self
+                {
+                    1 << index
+                }
+
+                fn lowest(count: u32) -> Self
+                {
+                    if count >= Self::BITS { !0 } else { (1 << count) - 1 }
+                }
+
+                fn count_ones(self) -> u32
+                {
+                    <$storage>::count_ones(self)
+                }
+
+                fn trailing_zeros(self) -> u32
+                {
+                    <$storage>::trailing_zeros(self)
+                }
+
+                fn without_lowest(self) -> Self
+                {
+                    self & self.wrapping_sub(1)
+                }
+            }
+        )*
+    };
+}
+
+candidate_storage!(u16, u32, u64, u128);
+
+
+// The number of symbols of a grid, to pick a storage for it at compile time.
+pub struct Symbols<const N: usize>;
+
+// The storage pencil notes, the solvers, the factory and the game use for a grid by default:
+// the smallest of u16, u32, u64 and u128 with a bit for every number. Bounds are written as
+// `Symbols<N_ROWS>: DefaultStorage`, as a valid grid has N_ROWS numbers.
+pub trait DefaultStorage
+{
+    type Storage: CandidateStorage;
+}
+
+pub type StorageFor<const N: usize> = <Symbols<N> as DefaultStorage>::Storage;
+
+
+macro_rules! default_storage {
+    ($storage:ty: $($symbols:literal),*) => {
+        $(
+            impl DefaultStorage for Symbols<$symbols> {
+                type Storage = $storage;
+            }
+        )*
+    };
+}
+
+default_storage!(u16:
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16
+);
+default_storage!(u32:
+    17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32
+);
+default_storage!(u64:
+    33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48,
+    49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63, 64
+);
+default_storage!(u128:
+    65, 66, 67, 68, 69, 70, 71, 72, 73, 74, 75, 76, 77, 78, 79, 80,
+    81, 82, 83, 84, 85, 86, 87, 88, 89, 90, 91, 92, 93, 94, 95, 96,
+    97, 98, 99, 100, 101, 102, 103, 104, 105, 106, 107, 108, 109, 110, 111, 112,
+    113, 114, 115, 116, 117, 118, 119, 120, 121, 122, 123, 124, 125, 126, 127, 128
+);
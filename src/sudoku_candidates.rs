@@ -0,0 +1,187 @@
+use std::fmt;
+use std::ops::{BitAnd, BitOr, Sub};
+
+use crate::sudoku_candidate_storage::CandidateStorage;
+use crate::sudoku_pencil_notes::PossibilityIterator;
+
+
+// The numbers that can still go into a cell. Numbers are 1-based and the bit for a number
+// is number - 1, so go through this type rather than shifting by hand.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Candidates<S: CandidateStorage = u32>
+{
+    bits: S,
+}
+
+
+impl<S: CandidateStorage> Candidates<S> {
+
+    pub fn new() -> Self
+    {
+        Candidates { bits: S::EMPTY }
+    }
+
+    // the numbers 1..=max_number
+    pub fn all(max_number: u32) -> Self
+    {
+        Candidates { bits: S::lowest(max_number) }
+    }
+
+    pub fn from_bits(bits: S) -> Self
+    {
+        Candidates { bits }
+    }
+
+    pub fn from_numbers(numbers: &[u32]) -> Self
+    {
+        numbers.iter().copied().collect()
+    }
+
+    pub fn bits(self) -> S
+    {
+        self.bits
+    }
+
+    // numbers outside 1..=S::BITS have no bit: they are never contained,
+    // and inserting or removing them does nothing
+    pub fn contains(self, number: u32) -> bool
+    {
+        Self::index(number).is_some_and(|index| self.bits.has_bit(index))
+    }
+
+    pub fn insert(&mut self, number: u32)
+    {
+        if let Some(index) = Self::index(number) {
+            self.bits |= S::bit(index);
+        }
+    }
+
+    pub fn remove(&mut self, number: u32)
+    {
+        if let Some(index) = Self::index(number) {
+            self.bits &= !S::bit(index);
+        }
+    }
+
+    fn index(number: u32) -> Option<u32>
+    {
+        (1..=S::BITS).contains(&number).then(|| number - 1)
+    }
+
+    pub fn len(self) -> usize
+    {
+        self.bits.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool
+    {
+        self.bits.is_empty()
+    }
+
+    // the number if it is the only one left
+    pub fn single(self) -> Option<u32>
+    {
+        (self.len() == 1).then(|| self.bits.trailing_zeros() + 1)
+    }
+
+    pub fn union(self, other: Self) -> Self
+    {
+        Candidates { bits: self.bits | other.bits }
+    }
+
+    pub fn intersection(self, other: Self) -> Self
+    {
+        Candidates { bits: self.bits & other.bits }
+    }
+
+    pub fn difference(self, other: Self) -> Self
+    {
+        Candidates { bits: self.bits & !other.bits }
+    }
+
+    pub fn is_subset(self, other: Self) -> bool
+    {
+        self.difference(other).is_empty()
+    }
+
+    // the numbers in ascending order
+    pub fn iter(self) -> PossibilityIterator<S>
+    {
+        PossibilityIterator::new(self.bits)
+    }
+}
+
+
+impl<S: CandidateStorage> BitOr for Candidates<S> {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+
+impl<S: CandidateStorage> BitAnd for Candidates<S> {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        self.intersection(other)
+    }
+}
+
+
+impl<S: CandidateStorage> Sub for Candidates<S> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.difference(other)
+    }
+}
+
+
+impl<S: CandidateStorage> IntoIterator for Candidates<S> {
+    type Item = u32;
+    type IntoIter = PossibilityIterator<S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+
+impl<S: CandidateStorage> FromIterator<u32> for Candidates<S> {
+
+    fn from_iter<I: IntoIterator<Item = u32>>(numbers: I) -> Self {
+
+        let mut candidates = Self::new();
+        for number in numbers {
+            candidates.insert(number);
+        }
+        candidates
+    }
+}
+
+
+// {1, 4, 7}
+impl<S: CandidateStorage> fmt::Display for Candidates<S> {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+
+        write!(f, "{{")?;
+        for (index, number) in self.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", number)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+
+impl<S: CandidateStorage> fmt::Debug for Candidates<S> {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Candidates{}", self)
+    }
+}
@@ -0,0 +1,69 @@
+use std::fmt;
+
+use crate::sudoku_error::SudokuError;
+
+
+// A number that fits the grid it was checked against, 1..=BOX_H * BOX_W.
+// The grid is part of the type, so a Digit can only be used with that grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Digit<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize = 3, const BOX_W: usize = 3>(u32);
+
+
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize> Digit<N_ROWS, N_COLS, BOX_H, BOX_W> {
+
+    pub fn new(value: u32) -> Result<Self, SudokuError>
+    {
+        let max_number = (BOX_H * BOX_W) as u32;
+
+        if value == 0 || value > max_number {
+            return Err(SudokuError::InvalidDigit { value, max_number });
+        }
+
+        Ok(Digit(value))
+    }
+
+    pub fn get(self) -> u32
+    {
+        self.0
+    }
+}
+
+
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize> fmt::Display for Digit<N_ROWS, N_COLS, BOX_H, BOX_W> {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+
+// A cell that lies inside the grid it was checked against, which is part of the type as for Digit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CellPos<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize = 3, const BOX_W: usize = 3>
+{
+    row: usize,
+    col: usize,
+}
+
+
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize> CellPos<N_ROWS, N_COLS, BOX_H, BOX_W> {
+
+    pub fn new(row: usize, col: usize) -> Result<Self, SudokuError>
+    {
+        if row >= N_ROWS || col >= N_COLS {
+            return Err(SudokuError::CellOutOfRange { row, col, n_rows: N_ROWS, n_cols: N_COLS });
+        }
+
+        Ok(CellPos { row, col })
+    }
+
+    pub fn row(self) -> usize
+    {
+        self.row
+    }
+
+    pub fn col(self) -> usize
+    {
+        self.col
+    }
+}
@@ -0,0 +1,325 @@
+use crate::sudoku::Sudoku;
+use crate::sudoku_solver::SudokuSolver;
+
+
+// Knuth's Algorithm X on dancing links: a sparse 0/1 matrix whose nodes are kept in
+// circular doubly linked lists, so covering and uncovering a column is cheap.
+// Primary columns have to be covered exactly once, secondary columns at most once.
+#[derive(Debug, Clone)]
+pub struct ExactCover
+{
+    // node 0 is the root, nodes 1..=n_columns the column headers, the rest belong to rows
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    column: Vec<usize>,
+    row: Vec<usize>,
+    // number of nodes in every column, indexed by header
+    size: Vec<usize>,
+    covered: Vec<bool>,
+    // the first node of every row
+    rows: Vec<usize>,
+    // rows chosen up front with `select`
+    selected: Vec<usize>,
+}
+
+
+impl ExactCover {
+
+    pub fn new(primary_columns: usize, secondary_columns: usize) -> Self
+    {
+        let n_columns = primary_columns + secondary_columns;
+        let n_headers = n_columns + 1;
+
+        let mut cover = ExactCover {
+            left: (0..n_headers).collect(),
+            right: (0..n_headers).collect(),
+            up: (0..n_headers).collect(),
+            down: (0..n_headers).collect(),
+            column: (0..n_headers).collect(),
+            row: vec![usize::MAX; n_headers],
+            size: vec![0; n_headers],
+            covered: vec![false; n_headers],
+            rows: Vec::new(),
+            selected: Vec::new(),
+        };
+
+        // only the primary columns are linked to the root, secondary ones never have to be chosen
+        for header in 1..=primary_columns {
+            cover.left[header] = header - 1;
+            cover.right[header - 1] = header;
+        }
+        cover.left[0] = primary_columns;
+        cover.right[primary_columns] = 0;
+
+        cover
+    }
+
+    // adds a row covering the given (0 based) columns and returns its index
+    pub fn add_row(&mut self, columns: &[usize]) -> usize
+    {
+        let index = self.rows.len();
+        let first = self.left.len();
+
+        for (i, &col) in columns.iter().enumerate() {
+
+            let header = col + 1;
+            let node = first + i;
+
+            self.column.push(header);
+            self.row.push(index);
+
+            // append to the bottom of the column
+            self.up.push(self.up[header]);
+            self.down.push(header);
+            let last = self.up[header];
+            self.down[last] = node;
+            self.up[header] = node;
+            self.size[header] += 1;
+
+            // and to the end of the row
+            if i == 0 {
+                self.left.push(node);
+                self.right.push(node);
+            } else {
+                self.left.push(node - 1);
+                self.right.push(first);
+                self.right[node - 1] = node;
+                self.left[first] = node;
+            }
+        }
+
+        self.rows.push(first);
+        index
+    }
+
+    // puts the row into every solution, e.g. for the givens of a puzzle.
+    // returns false if it collides with a row selected before.
+    pub fn select(&mut self, row: usize) -> bool
+    {
+        let first = self.rows[row];
+
+        let mut node = first;
+        loop {
+            if self.covered[self.column[node]] {
+                return false;
+            }
+            node = self.right[node];
+            if node == first {
+                break;
+            }
+        }
+
+        loop {
+            self.cover(self.column[node]);
+            node = self.right[node];
+            if node == first {
+                break;
+            }
+        }
+
+        self.selected.push(row);
+        true
+    }
+
+    // hands the rows of every solution to `found`, which returns true to stop the search.
+    // returns true if the search was stopped.
+    pub fn search(&mut self, found: &mut dyn FnMut(&[usize]) -> bool) -> bool
+    {
+        let mut solution = self.selected.clone();
+        self.search_from(&mut solution, found)
+    }
+
+    fn search_from(&mut self, solution: &mut Vec<usize>, found: &mut dyn FnMut(&[usize]) -> bool) -> bool
+    {
+        if self.right[0] == 0 {
+            return found(solution);
+        }
+
+        // the column with the fewest rows left keeps the tree small
+        let mut header = self.right[0];
+        let mut col = header;
+        while header != 0 {
+            if self.size[header] < self.size[col] {
+                col = header;
+            }
+            header = self.right[header];
+        }
+
+        if self.size[col] == 0 {
+            return false;
+        }
+
+        self.cover(col);
+
+        let mut stopped = false;
+        let mut node = self.down[col];
+
+        while node != col && !stopped {
+
+            solution.push(self.row[node]);
+
+            let mut other = self.right[node];
+            while other != node {
+                self.cover(self.column[other]);
+                other = self.right[other];
+            }
+
+            stopped = self.search_from(solution, found);
+
+            let mut other = self.left[node];
+            while other != node {
+                self.uncover(self.column[other]);
+                other = self.left[other];
+            }
+
+            solution.pop();
+            node = self.down[node];
+        }
+
+        self.uncover(col);
+
+        stopped
+    }
+
+    fn cover(&mut self, col: usize)
+    {
+        self.covered[col] = true;
+        self.right[self.left[col]] = self.right[col];
+        self.left[self.right[col]] = self.left[col];
+
+        let mut node = self.down[col];
+        while node != col {
+            let mut other = self.right[node];
+            while other != node {
+                self.down[self.up[other]] = self.down[other];
+                self.up[self.down[other]] = self.up[other];
+                self.size[self.column[other]] -= 1;
+                other = self.right[other];
+            }
+            node = self.down[node];
+        }
+    }
+
+    fn uncover(&mut self, col: usize)
+    {
+        let mut node = self.up[col];
+        while node != col {
+            let mut other = self.left[node];
+            while other != node {
+                self.size[self.column[other]] += 1;
+                self.down[self.up[other]] = other;
+                self.up[self.down[other]] = other;
+                other = self.left[other];
+            }
+            node = self.up[node];
+        }
+
+        self.right[self.left[col]] = col;
+        self.left[self.right[col]] = col;
+        self.covered[col] = false;
+    }
+}
+
+
+// Solves sudokus as an exact cover problem: every candidate (row, col, number) is a matrix row
+// covering its cell, and the number in its row, column and square.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DlxSolver;
+
+
+impl DlxSolver {
+
+    // the exact cover of the sudoku with its givens selected, None if the givens collide.
+    // matrix row (r * N_COLS + c) * max_number + number - 1 is the candidate (r, c, number).
+    pub fn exact_cover<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize>(sudoku: &Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>) -> Option<ExactCover>
+    {
+        let n = sudoku.max_number() as usize;
+        let squares_per_row = N_COLS / BOX_W;
+
+        let cell_columns = 0;
+        let row_columns = cell_columns + N_ROWS * N_COLS;
+        let col_columns = row_columns + N_ROWS * n;
+        let square_columns = col_columns + N_COLS * n;
+
+        let mut cover = ExactCover::new(square_columns + n * n, 0);
+
+        for r in 0..N_ROWS {
+            for c in 0..N_COLS {
+                let square = (r / BOX_H) * squares_per_row + c / BOX_W;
+
+                for digit in 0..n {
+                    cover.add_row(&[
+                        cell_columns + r * N_COLS + c,
+                        row_columns + r * n + digit,
+                        col_columns + c * n + digit,
+                        square_columns + square * n + digit,
+                    ]);
+                }
+            }
+        }
+
+        for r in 0..N_ROWS {
+            for c in 0..N_COLS {
+                let number = sudoku.board[r][c] as usize;
+
+                if number != 0 && (number > n || !cover.select((r * N_COLS + c) * n + number - 1)) {
+                    return None;
+                }
+            }
+        }
+
+        Some(cover)
+    }
+
+    fn search<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize>(sudoku: &Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>, found: &mut dyn FnMut(&Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>) -> bool)
+    {
+        let Some(mut cover) = Self::exact_cover(sudoku) else {
+            return;
+        };
+
+        let n = sudoku.max_number() as usize;
+
+        cover.search(&mut |rows| {
+            let mut solution = *sudoku;
+            for &row in rows {
+                let cell = row / n;
+                solution.board[cell / N_COLS][cell % N_COLS] = (row % n) as u32 + 1;
+            }
+            found(&solution)
+        });
+    }
+}
+
+
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize> SudokuSolver<N_ROWS, N_COLS, BOX_H, BOX_W> for DlxSolver {
+
+    fn solve(&self, sudoku: &Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>) -> Option<Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>>
+    {
+        let mut solution = None;
+
+        Self::search(sudoku, &mut |sudoku| {
+            solution = Some(*sudoku);
+            true
+        });
+
+        solution
+    }
+
+    fn count_solutions(&self, sudoku: &Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>, limit: usize) -> usize
+    {
+        let mut count = 0;
+
+        if limit == 0 {
+            return count;
+        }
+
+        Self::search(sudoku, &mut |_| {
+            count += 1;
+            count >= limit
+        });
+
+        count
+    }
+}
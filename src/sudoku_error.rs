@@ -0,0 +1,46 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::sudoku_iterator::Unit;
+
+
+// What is wrong with a value, a coordinate or the board itself.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SudokuError
+{
+    // a number outside 1..=max_number
+    InvalidDigit { value: u32, max_number: u32 },
+    // a row or column outside the grid
+    CellOutOfRange { row: usize, col: usize, n_rows: usize, n_cols: usize },
+    // a board entry that is neither empty (0) nor a valid number
+    InvalidEntry { row: usize, col: usize, value: u32, max_number: u32 },
+    // the number shows up more than once in the unit, in these cells
+    Conflict { unit: Unit, number: u32, cells: Vec<(usize, usize)> },
+    // the cell holds a given, which can't be changed
+    GivenCell { row: usize, col: usize },
+}
+
+
+impl fmt::Display for SudokuError {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+
+        match self {
+            SudokuError::InvalidDigit { value, max_number } =>
+                write!(f, "{} is not a number between 1 and {}", value, max_number),
+            SudokuError::CellOutOfRange { row, col, n_rows, n_cols } =>
+                write!(f, "cell ({}, {}) is outside the {}x{} grid", row, col, n_rows, n_cols),
+            SudokuError::InvalidEntry { row, col, value, max_number } =>
+                write!(f, "cell ({}, {}) holds {}, expected 0 (empty) to {}", row, col, value, max_number),
+            SudokuError::Conflict { unit, number, cells } => {
+                let cells: Vec<String> = cells.iter().map(|(row, col)| format!("({}, {})", row, col)).collect();
+                write!(f, "{} shows up more than once in {:?}, in cells {}", number, unit, cells.join(", "))
+            },
+            SudokuError::GivenCell { row, col } =>
+                write!(f, "cell ({}, {}) holds a given", row, col),
+        }
+    }
+}
+
+
+impl Error for SudokuError {}
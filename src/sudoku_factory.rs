@@ -1,199 +1,275 @@
 use rand::RngCore;
 
 use crate::sudoku::Sudoku;
-use crate::sudoku_iterator::SudokuIteratorMode;
-use crate::sudoku_pencil_notes::{HiddenSingleIterator, PencilNotes, RandomBit};
+use crate::sudoku_candidate_storage::{DefaultStorage, StorageFor, Symbols};
+use crate::sudoku_iterator::{SudokuIterator, SudokuIteratorMode};
+use crate::sudoku_pencil_notes::{HiddenSingleIterator, RandomBit};
+use crate::sudoku_observer::{GenerationObserver, SilentObserver};
+use crate::sudoku_rating::{rate, Difficulty, Rating};
+use crate::sudoku_solver::SolverState;
 
-use crate::sudoku_fmt::*;
-use crate::sudoku_pencil_notes_fmt::*;
 
-use std::thread;
-use std::time::Duration;
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Symmetry
+{
+    None,
+    // a given at (row, col) is kept or removed together with (N_ROWS-1-row, N_COLS-1-col)
+    Rotational,
+}
 
-// const SLEEP_DURATION_SECS: Duration = Duration::from_millis(0);
-const SLEEP_DURATION_SECS: Duration = Duration::from_secs(1);
 
-pub struct SudokuFactory<const N_ROWS: usize, const N_COLS: usize>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Puzzle<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize = 3, const BOX_W: usize = 3>
 {
-    pub random_bit: RandomBit
+    pub puzzle: Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>,
+    pub solution: Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>,
 }
 
-impl<const N_ROWS: usize, const N_COLS: usize> SudokuFactory<N_ROWS, N_COLS> {
+
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize> Puzzle<N_ROWS, N_COLS, BOX_H, BOX_W> {
+
+    pub fn clues(&self) -> usize
+    {
+        self.puzzle.board.iter().flatten().filter(|&&number| number != 0).count()
+    }
+}
+
+
+pub struct SudokuFactory<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize = 3, const BOX_W: usize = 3>
+where
+    Symbols<N_ROWS>: DefaultStorage,
+{
+    pub random_bit: RandomBit,
+    pub observer: Box<dyn GenerationObserver<N_ROWS, N_COLS, BOX_H, BOX_W, StorageFor<N_ROWS>>>,
+}
+
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize> SudokuFactory<N_ROWS, N_COLS, BOX_H, BOX_W>
+where
+    Symbols<N_ROWS>: DefaultStorage,
+{
 
     const CHECK_CONSTRAINTS: () = {
         // Constraint 1: Minimum Size
-        assert!(N_ROWS > 3, "N_ROWS must be greater than 3");
-        assert!(N_COLS > 3, "N_COLS must be greater than 3");
+        assert!(BOX_H > 1, "BOX_H must be greater than 1");
+        assert!(BOX_W > 1, "BOX_W must be greater than 1");
 
-        // Constraint 2: Integer Size
-        let block_h = N_ROWS / 3;
-        let block_w = N_COLS / 3;
-        let symbols_needed = block_h * block_w;
+        // Constraint 2: Box Dimensions
+        // every row, column and box holds each number once, so all of them have BOX_H * BOX_W cells
+        assert!(N_ROWS == BOX_H * BOX_W, "N_ROWS must be BOX_H * BOX_W");
+        assert!(N_COLS == BOX_H * BOX_W, "N_COLS must be BOX_H * BOX_W");
 
+        // Constraint 3: Integer Size
+        let symbols_needed = BOX_H * BOX_W;
+
+        // the candidates have to fit the widest storage, see DefaultStorage
         assert!(
-            symbols_needed <= u32::BITS as usize, 
-            "The chosen type u32 is too small for this grid size!"
+            symbols_needed <= u128::BITS as usize,
+            "There is no candidate storage for this grid size!"
         );
     };
 
     pub fn new(rng: Box<dyn RngCore>) -> Self
     {
-        Self { random_bit: RandomBit::new(rng) }
+        Self::with_observer(rng, Box::new(SilentObserver))
     }
 
-    pub fn generate(&mut self) -> Sudoku<N_ROWS, N_COLS>
+    pub fn with_observer(rng: Box<dyn RngCore>, observer: Box<dyn GenerationObserver<N_ROWS, N_COLS, BOX_H, BOX_W, StorageFor<N_ROWS>>>) -> Self
     {
-        // Placeholder implementation
-        let mut sudoku = Sudoku { board: [[0; N_COLS]; N_ROWS] };
-        let mut pencil_notes = PencilNotes::<N_ROWS, N_COLS>::new();
+        let () = Self::CHECK_CONSTRAINTS;
+
+        Self { random_bit: RandomBit::new(rng), observer }
+    }
+
+    pub fn generate(&mut self) -> Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>
+    {
+        let mut state = SolverState::<N_ROWS, N_COLS, BOX_H, BOX_W, StorageFor<N_ROWS>>::empty();
+        let geometry = state.pencil_notes.geometry();
+
+        // the state before every random guess together with the guess itself,
+        // so a guess that runs into a contradiction can be taken back
+        let mut guesses: Vec<(SolverState<N_ROWS, N_COLS, BOX_H, BOX_W, StorageFor<N_ROWS>>, usize, usize, u32)> = Vec::new();
+
+        let mut iterations = 0;
+
+        while !state.sudoku.is_complete() {
+
+            iterations += 1;
 
-        for iterations in 0..(N_ROWS * N_COLS) {
-            
             let mut found_hidden_singles = false;
 
             for row in 0..N_ROWS {
                 for col in 0..N_COLS {
-                    if sudoku.board[row][col] != 0 {
+                    if state.sudoku.board[row][col] != 0 {
                         continue;
                     }
 
-                    if let Some(number) = pencil_notes.get_possibility(row, col) {
-
-                        println!("{}: Naked single found at ({}, {}) with possibility {}", iterations+1, row, col, number);
+                    if let Some(number) = state.pencil_notes.get_possibility(row, col) {
 
-                        sudoku.board[row][col] = number;
-                        pencil_notes.set_possibility(row, col, number);
-                        pencil_notes.eliminate_possibility(row, col, number);
+                        self.observer.naked_single(iterations, row, col, number);
+                        state.place(row, col, number);
 
-                        // thread::sleep(SLEEP_DURATION_SECS);
                         found_hidden_singles = true;
                     }
                 }
             }
 
             // handle naked and hidden pairs
-            for cnt in 0..N_ROWS {
-                pencil_notes.handle_naked_pairs(cnt, 0, SudokuIteratorMode::Row);
-                pencil_notes.handle_naked_pairs(0, cnt, SudokuIteratorMode::Column);
-                pencil_notes.handle_hidden_pairs(cnt, 0, SudokuIteratorMode::Row);
-                pencil_notes.handle_hidden_pairs(0, cnt, SudokuIteratorMode::Column);
-            }
+            let mut eliminated = Vec::new();
 
-            for row in 0..N_ROWS/3 {
-                for col in 0..N_COLS/3 {
-                    pencil_notes.handle_naked_pairs(row*3, col*3, SudokuIteratorMode::Square);
-                    pencil_notes.handle_hidden_pairs(row*3, col*3, SudokuIteratorMode::Square);
-                }
+            for (unit, _) in geometry.units() {
+                let (row, col, mode) = unit.to_mode::<N_ROWS, N_COLS, BOX_H, BOX_W>();
+
+                eliminated.extend(state.pencil_notes.handle_naked_pairs(row, col, mode));
+                eliminated.extend(state.pencil_notes.handle_hidden_pairs(row, col, mode));
             }
 
-            for row in 0..N_ROWS/3 {
-                for col in 0..N_COLS/3 {
-                    for (r,c, possibility) in HiddenSingleIterator::<N_ROWS, N_COLS>::new(&pencil_notes, row*3, col*3, SudokuIteratorMode::Square) {
-                        
-                        if sudoku.board[r][c] == 0 {
-                            println!("{}: Hidden single square found at ({}, {}) with possibility {}", iterations+1, r, c, possibility);
-                            sudoku.board[r][c] = possibility;
-                            pencil_notes.set_possibility(r, c, possibility);
-                            pencil_notes.eliminate_possibility(r, c, possibility);
-
-                            // thread::sleep(SLEEP_DURATION_SECS);
-                            found_hidden_singles = true;
-                            // break;
-                        }
-                    }
-                }
+            for (row, col, number) in eliminated {
+                self.observer.pair_elimination(iterations, row, col, number);
             }
 
-            for row in 0..N_ROWS {
-                for (r,c, possibility) in HiddenSingleIterator::<N_ROWS, N_COLS>::new(&pencil_notes, row, 0, SudokuIteratorMode::Column) {
+            for (unit, _) in geometry.units() {
+                let (row, col, mode) = unit.to_mode::<N_ROWS, N_COLS, BOX_H, BOX_W>();
 
-                    if sudoku.board[r][c] == 0 {
-                        println!("{}: Hidden single column found at ({}, {}) with possibility {}", iterations+1, r, c, possibility);
-                        sudoku.board[r][c] = possibility;
-                        pencil_notes.set_possibility(r, c, possibility);
-                        pencil_notes.eliminate_possibility(r, c, possibility);
+                for (r, c, possibility) in HiddenSingleIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(&state.pencil_notes, row, col, mode) {
 
-                        // thread::sleep(SLEEP_DURATION_SECS);
-                        found_hidden_singles = true;
+                    if state.sudoku.board[r][c] == 0 && state.pencil_notes.has_possibility(r, c, possibility) {
+                        self.observer.hidden_single(iterations, r, c, possibility, mode);
+                        state.place(r, c, possibility);
 
-                        // break;
+                        found_hidden_singles = true;
                     }
                 }
             }
 
-            for col in 0..N_COLS {
-                for(r,c, possibility) in HiddenSingleIterator::<N_ROWS, N_COLS>::new(&pencil_notes, 0, col, SudokuIteratorMode::Row) {
-
-                    if sudoku.board[r][c] == 0 {
-                        println!("{}: Hidden single row found at ({}, {}) with possibility {}", iterations+1, r, c, possibility);
-                        sudoku.board[r][c] = possibility;
-                        pencil_notes.set_possibility(r, c, possibility);
-                        pencil_notes.eliminate_possibility(r, c, possibility);
-
-                        // thread::sleep(SLEEP_DURATION_SECS);
-                        found_hidden_singles = true;
+            if !found_hidden_singles
+                && let Some((row, col)) = state.pencil_notes.find_lowest_entropy_cell()
+                && state.sudoku.board[row][col] == 0 {
 
-                        break;
-                    }
+                let candidates = state.pencil_notes.get_possibilities(row, col);
+                if let Some(number) = self.random_bit.select_random_number(candidates)
+                {
+                    self.observer.random_guess(iterations, row, col, number);
+                    guesses.push((state.clone(), row, col, number));
+                    state.place(row, col, number);
                 }
             }
 
-            if !found_hidden_singles {
-
-                if let Some((row, col)) = pencil_notes.find_lowest_entropy_cell() {
-
-                    if sudoku.board[row][col] == 0 {
-
-                        let mask = pencil_notes.get_possibilities(row, col);
-                        if let Some(selected_bit) = self.random_bit.select_random_bit(mask)
-                        {
-                            let number = selected_bit + 1;
-                            
-                            if sudoku.board[row][col] == 0 {
-                                
-                                println!("{}: Filling cell ({}, {}) with random number {}", iterations+1, row, col, number);
-                                sudoku.board[row][col] = number;
-                                pencil_notes.set_possibility(row, col, number);
-                                pencil_notes.eliminate_possibility(row, col, number);
-                                // break;
-                            }
-                            // thread::sleep(SLEEP_DURATION_SECS);
-                        }
-                        else
-                        {
-                            // assert!(false, "No valid options left for cell ({}, {})", row, col);
-                        }
-                    }
-                }
+            self.observer.snapshot(iterations, &state.sudoku, &state.pencil_notes);
+
+            // a contradiction means the last random guess was wrong: go back to the state
+            // before it and rule the guessed number out for that cell
+            while !state.sudoku.is_valid() || !state.is_consistent() {
+
+                self.observer.backtrack(iterations, &state.sudoku);
+
+                // an empty board always has a solution, so running out of guesses means a deduction was unsound
+                let (previous, row, col, number) = guesses.pop()
+                    .unwrap_or_else(|| unreachable!("contradiction without a guess to take back, a deduction removed a valid candidate"));
+
+                state = previous;
+                state.pencil_notes.remove_possibility(row, col, number);
             }
+        }
+
+        state.sudoku
+    }
+
+    // generates a solution grid and removes givens in random order as long as the
+    // solution stays unique. stops at `target_clues`, or earlier if no further
+    // given can be removed without allowing a second solution.
+    pub fn generate_puzzle(&mut self, target_clues: usize, symmetry: Symmetry) -> Puzzle<N_ROWS, N_COLS, BOX_H, BOX_W>
+    {
+        let (solution, puzzles) = self.remove_givens(target_clues, symmetry);
+        let puzzle = *puzzles.last().expect("starts with the solution");
+
+        Puzzle { puzzle, solution }
+    }
+
+    // the solution and the puzzle after every accepted removal, starting with the solution itself
+    fn remove_givens(&mut self, target_clues: usize, symmetry: Symmetry) -> (Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>, Vec<Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>>)
+    {
+        let solution = self.generate();
+        let mut puzzle = solution;
+        let mut puzzles = vec![solution];
+        let mut clues = N_ROWS * N_COLS;
 
-            println!("\nCurrent Sudoku State:\n{}", sudoku);
-            println!("Current Pencil Notes State:\n{}", pencil_notes);
-            thread::sleep(SLEEP_DURATION_SECS);
+        let mut cells: Vec<(usize, usize)> = SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(0, 0, SudokuIteratorMode::All).collect();
+        self.random_bit.shuffle(&mut cells);
 
-            let valid = sudoku.is_valid();
-            if !valid {
-                println!("Sudoku state is invalid!\n{}", sudoku);
-                // assert!(false, "Sudoku state is invalid!");
+        for (row, col) in cells {
+
+            if clues <= target_clues {
                 break;
             }
 
-            if sudoku.is_complete() {
-                // println!("Sudoku generation complete after {} iterations!", iterations + 1);
-                break;
+            if puzzle.board[row][col] == 0 {
+                continue;
+            }
+
+            let mut removed = vec![(row, col)];
+            if symmetry == Symmetry::Rotational {
+                let partner = (N_ROWS - 1 - row, N_COLS - 1 - col);
+                if partner != (row, col) && puzzle.board[partner.0][partner.1] != 0 {
+                    removed.push(partner);
+                }
+            }
+
+            if clues - removed.len() < target_clues {
+                continue;
             }
 
-            let valid = pencil_notes.check();
-            if !valid {
-                println!("Pencil notes has cells with no possibilities!");
+            let mut candidate = puzzle;
+            for &(r, c) in &removed {
+                candidate.board[r][c] = 0;
             }
 
+            if candidate.has_unique_solution() {
+                puzzle = candidate;
+                puzzles.push(puzzle);
+                clues -= removed.len();
+            }
         }
 
-        sudoku
+        (solution, puzzles)
     }
 
+    // generates puzzles until one is rated `difficulty`, giving up after `max_attempts`.
+    // givens are removed as long as the solution stays unique, then the longest run of
+    // removals that isn't rated harder than `difficulty` is kept. an attempt that ends up
+    // easier is thrown away.
+    pub fn generate_puzzle_with_difficulty(&mut self, difficulty: Difficulty, symmetry: Symmetry, max_attempts: usize) -> Option<(Puzzle<N_ROWS, N_COLS, BOX_H, BOX_W>, Rating)>
+    {
+        for _ in 0..max_attempts {
+
+            let (solution, puzzles) = self.remove_givens(0, symmetry);
+
+            // removing givens rarely makes a puzzle easier, so a binary search over the
+            // removals finds the cut with only a few ratings instead of one per removal.
+            // puzzles[..kept] are rated at most `difficulty`, puzzles[harder..] above it,
+            // the solution itself needs no technique at all.
+            let (mut kept, mut harder) = (1, puzzles.len());
+            let mut rating = None;
+
+            while kept < harder {
+                let middle = (kept + harder) / 2;
+                let middle_rating = rate(&puzzles[middle]);
+
+                if middle_rating.difficulty <= difficulty {
+                    kept = middle + 1;
+                    rating = Some(middle_rating);
+                } else {
+                    harder = middle;
+                }
+            }
 
-}
+            let puzzle = puzzles[kept - 1];
+            let rating = rating.unwrap_or_else(|| rate(&puzzle));
 
+            if rating.difficulty == difficulty {
+                return Some((Puzzle { puzzle, solution }, rating));
+            }
+        }
 
+        None
+    }
+}
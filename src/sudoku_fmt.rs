@@ -3,15 +3,13 @@ use std::fmt;
 
 use crate::sudoku::Sudoku;
 
-impl<const N_ROWS: usize, const N_COLS: usize> fmt::Display for Sudoku<N_ROWS, N_COLS> {
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize> fmt::Display for Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W> {
 
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         
-        // 1. APPLY YOUR LOGIC
-        // We calculate block size by strictly dividing by 3.
-        // We use .max(1) to prevent "divide by zero" errors if rows/cols < 3.
-        let block_h = (N_ROWS / 3).max(1);
-        let block_w = (N_COLS / 3).max(1);
+        // 1. BLOCK SIZE
+        let block_h = BOX_H;
+        let block_w = BOX_W;
 
         // 2. Dynamic Padding
         let max_dim = cmp::max(N_ROWS, N_COLS);
@@ -44,7 +42,7 @@ impl<const N_ROWS: usize, const N_COLS: usize> fmt::Display for Sudoku<N_ROWS, N
             write!(f, "|")?;
 
             for (col_idx, cell) in row.iter().enumerate() {
-                let val = if *cell <= 0 { ".".to_string() } else { cell.to_string() };
+                let val = if *cell == 0 { ".".to_string() } else { cell.to_string() };
 
                 write!(f, " {:>width$} ", val, width = max_val_width)?;
 
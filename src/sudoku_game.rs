@@ -0,0 +1,157 @@
+use crate::sudoku::Sudoku;
+use crate::sudoku_candidate_storage::{DefaultStorage, StorageFor, Symbols};
+use crate::sudoku_cell::{CellPos, Digit};
+use crate::sudoku_error::SudokuError;
+use crate::sudoku_pencil_notes::PencilNotes;
+
+
+// A puzzle being played. The givens never change, the player's numbers and pencil marks
+// are kept apart from them, and the computed pencil notes follow the board (givens + entries).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize = 3, const BOX_W: usize = 3>
+where
+    Symbols<N_ROWS>: DefaultStorage,
+{
+    pub givens: Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>,
+    // numbers entered by the player, 0 where there is none
+    pub entries: Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>,
+    // pencil marks made by the player, empty until marked
+    pub marks: PencilNotes<N_ROWS, N_COLS, BOX_H, BOX_W, StorageFor<N_ROWS>>,
+    // the candidates left by the board
+    pub notes: PencilNotes<N_ROWS, N_COLS, BOX_H, BOX_W, StorageFor<N_ROWS>>,
+    // None unless the givens have exactly one solution, as entries can't be checked otherwise
+    pub solution: Option<Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>>,
+}
+
+
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize> Game<N_ROWS, N_COLS, BOX_H, BOX_W>
+where
+    Symbols<N_ROWS>: DefaultStorage,
+{
+
+    // fails if a given is not a number of the grid or clashes with another one
+    pub fn try_new(givens: Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>) -> Result<Self, SudokuError>
+    {
+        let givens = Sudoku::try_new(givens.board)?;
+        givens.validate()?;
+
+        let mut marks = PencilNotes::new();
+        marks.clear();
+
+        Ok(Game {
+            givens,
+            entries: Sudoku::new([[0; N_COLS]; N_ROWS]),
+            marks,
+            notes: PencilNotes::from_sudoku(&givens),
+            solution: givens.unique_solution(),
+        })
+    }
+
+    pub fn is_given(&self, row: usize, col: usize) -> Result<bool, SudokuError>
+    {
+        let pos = CellPos::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(row, col)?;
+
+        Ok(self.givens.get(pos).is_some())
+    }
+
+    // the givens together with the player's numbers
+    pub fn board(&self) -> Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>
+    {
+        let mut board = self.givens;
+
+        for r in 0..N_ROWS {
+            for c in 0..N_COLS {
+                if self.entries.board[r][c] != 0 {
+                    board.board[r][c] = self.entries.board[r][c];
+                }
+            }
+        }
+
+        board
+    }
+
+    // puts the player's number into the cell, 0 erases it
+    pub fn enter(&mut self, row: usize, col: usize, value: u32) -> Result<(), SudokuError>
+    {
+        let pos = self.open_cell(row, col)?;
+
+        self.entries.try_set(pos.row(), pos.col(), value)?;
+        if value != 0 {
+            self.marks.clear_possibilities(pos.row(), pos.col());
+        }
+        self.notes = PencilNotes::from_sudoku(&self.board());
+
+        Ok(())
+    }
+
+    pub fn erase(&mut self, row: usize, col: usize) -> Result<(), SudokuError>
+    {
+        self.enter(row, col, 0)
+    }
+
+    // adds the pencil mark if it is not there, removes it otherwise
+    pub fn toggle_mark(&mut self, row: usize, col: usize, number: u32) -> Result<(), SudokuError>
+    {
+        let pos = self.open_cell(row, col)?;
+        let digit = Digit::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(number)?;
+
+        if self.marks.has_possibility(pos.row(), pos.col(), digit.get()) {
+            self.marks.remove_possibility(pos.row(), pos.col(), digit.get());
+        } else {
+            self.marks.add_possibility(pos.row(), pos.col(), digit.get());
+        }
+
+        Ok(())
+    }
+
+    fn open_cell(&self, row: usize, col: usize) -> Result<CellPos<N_ROWS, N_COLS, BOX_H, BOX_W>, SudokuError>
+    {
+        if self.is_given(row, col)? {
+            return Err(SudokuError::GivenCell { row, col });
+        }
+
+        CellPos::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(row, col)
+    }
+
+    // Some(true) if the player's number matches the solution,
+    // None if there is no number or no solution to check against, an error if the cell is out of range
+    pub fn check_entry(&self, row: usize, col: usize) -> Result<Option<bool>, SudokuError>
+    {
+        let pos = CellPos::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(row, col)?;
+
+        let (Some(solution), Some(digit)) = (self.solution.as_ref(), self.entries.get(pos)) else {
+            return Ok(None);
+        };
+
+        Ok(Some(solution.get(pos) == Some(digit)))
+    }
+
+    // the cells whose number does not match the solution
+    pub fn mistakes(&self) -> Vec<(usize, usize)>
+    {
+        let mut mistakes = Vec::new();
+
+        for r in 0..N_ROWS {
+            for c in 0..N_COLS {
+                if self.check_entry(r, c) == Ok(Some(false)) {
+                    mistakes.push((r, c));
+                }
+            }
+        }
+
+        mistakes
+    }
+
+    pub fn is_solved(&self) -> bool
+    {
+        self.solution == Some(self.board())
+    }
+
+    // back to the givens: the player's numbers and marks are gone
+    pub fn reset(&mut self)
+    {
+        self.entries.reset();
+        self.marks.clear();
+        self.notes = PencilNotes::from_sudoku(&self.givens);
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt;
+use std::sync::RwLock;
+
+use crate::sudoku_iterator::{SudokuIterator, SudokuIteratorMode, Unit};
+
+
+// one table per grid size, built on first use and kept for the rest of the program
+static GEOMETRIES: RwLock<Vec<&'static Geometry>> = RwLock::new(Vec::new());
+
+
+// The units and peers of every cell of a grid size, worked out once instead of scanning
+// the whole board every time. Get the table for a size with `Geometry::of`, which takes a lock,
+// so look it up once and keep the reference, like PencilNotes does.
+pub struct Geometry
+{
+    pub n_rows: usize,
+    pub n_cols: usize,
+    pub box_h: usize,
+    pub box_w: usize,
+    // in the order of Unit::all
+    units: Vec<Unit>,
+    unit_cells: Vec<Vec<(usize, usize)>>,
+    // indexed by row * n_cols + col: the cell together with its peers, top to bottom and left to right
+    affected: Vec<Vec<(usize, usize)>>,
+    // the same without the cell itself
+    peers: Vec<Vec<(usize, usize)>>,
+    // the row, column and square of every cell, as indices into `units`
+    cell_units: Vec<[usize; 3]>,
+}
+
+
+// there is one table per grid size, so the size is all there is to compare or print
+impl PartialEq for Geometry {
+
+    fn eq(&self, other: &Self) -> bool
+    {
+        (self.n_rows, self.n_cols, self.box_h, self.box_w) == (other.n_rows, other.n_cols, other.box_h, other.box_w)
+    }
+}
+
+impl Eq for Geometry {}
+
+impl fmt::Debug for Geometry {
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_struct("Geometry")
+            .field("n_rows", &self.n_rows)
+            .field("n_cols", &self.n_cols)
+            .field("box_h", &self.box_h)
+            .field("box_w", &self.box_w)
+            .finish_non_exhaustive()
+    }
+}
+
+
+impl Geometry {
+
+    pub fn of<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize>() -> &'static Geometry
+    {
+        let find = |geometries: &[&'static Geometry]| geometries.iter().copied().find(|geometry| geometry.n_rows == N_ROWS && geometry.n_cols == N_COLS && geometry.box_h == BOX_H && geometry.box_w == BOX_W);
+
+        if let Some(geometry) = find(&GEOMETRIES.read().unwrap()) {
+            return geometry;
+        }
+
+        let mut geometries = GEOMETRIES.write().unwrap();
+
+        // another thread may have built it while we were waiting for the lock
+        if let Some(geometry) = find(&geometries) {
+            return geometry;
+        }
+
+        let geometry: &'static Geometry = Box::leak(Box::new(Self::new::<N_ROWS, N_COLS, BOX_H, BOX_W>()));
+        geometries.push(geometry);
+
+        geometry
+    }
+
+    fn new<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize>() -> Self
+    {
+        let units = Unit::all::<N_ROWS, N_COLS, BOX_H, BOX_W>();
+        let unit_cells: Vec<Vec<(usize, usize)>> = units.iter().map(|unit| unit.iter::<N_ROWS, N_COLS, BOX_H, BOX_W>().collect()).collect();
+
+        let mut affected = Vec::with_capacity(N_ROWS * N_COLS);
+        let mut peers = Vec::with_capacity(N_ROWS * N_COLS);
+        let mut cell_units = Vec::with_capacity(N_ROWS * N_COLS);
+
+        for row in 0..N_ROWS {
+            for col in 0..N_COLS {
+
+                let cells: Vec<(usize, usize)> = (0..N_ROWS)
+                    .flat_map(|r| (0..N_COLS).map(move |c| (r, c)))
+                    .filter(|&(r, c)| (r, c) == (row, col) || SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::sees(row, col, r, c))
+                    .collect();
+
+                peers.push(cells.iter().copied().filter(|&cell| cell != (row, col)).collect());
+                affected.push(cells);
+
+                let square = Unit::from_mode::<N_ROWS, N_COLS, BOX_H, BOX_W>(row, col, SudokuIteratorMode::Square).unwrap();
+                let index = |unit: Unit| units.iter().position(|&other| other == unit).unwrap();
+                cell_units.push([index(Unit::Row(row)), index(Unit::Column(col)), index(square)]);
+            }
+        }
+
+        Geometry { n_rows: N_ROWS, n_cols: N_COLS, box_h: BOX_H, box_w: BOX_W, units, unit_cells, affected, peers, cell_units }
+    }
+
+    // every unit with its cells: all rows, then all columns, then all squares
+    pub fn units(&self) -> impl Iterator<Item = (Unit, &[(usize, usize)])>
+    {
+        self.units.iter().copied().zip(self.unit_cells.iter().map(Vec::as_slice))
+    }
+
+    pub fn unit(&self, unit: Unit) -> &[(usize, usize)]
+    {
+        let index = match unit {
+            Unit::Row(row) => row,
+            Unit::Column(col) => self.n_rows + col,
+            Unit::Square(square) => self.n_rows + self.n_cols + square,
+        };
+
+        &self.unit_cells[index]
+    }
+
+    // the row, column and square of the cell
+    pub fn units_of(&self, row: usize, col: usize) -> [Unit; 3]
+    {
+        self.cell_units[row * self.n_cols + col].map(|index| self.units[index])
+    }
+
+    // the cells sharing a unit with the cell
+    pub fn peers(&self, row: usize, col: usize) -> &[(usize, usize)]
+    {
+        &self.peers[row * self.n_cols + col]
+    }
+
+    // the peers and the cell itself, in the order SudokuIteratorMode::Affected visits them
+    pub fn affected(&self, row: usize, col: usize) -> &[(usize, usize)]
+    {
+        &self.affected[row * self.n_cols + col]
+    }
+}
@@ -1,3 +1,6 @@
+use crate::sudoku_geometry::Geometry;
+
+
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum SudokuIteratorMode
@@ -10,8 +13,71 @@ pub enum SudokuIteratorMode
 }
 
 
+// A row, column or square of the board. Squares are numbered left to right, top to bottom.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Unit
+{
+    Row(usize),
+    Column(usize),
+    Square(usize),
+}
+
+
+impl Unit {
+
+    // every unit of the board: all rows, then all columns, then all squares
+    pub fn all<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize>() -> Vec<Unit>
+    {
+        let squares = (N_ROWS / BOX_H) * (N_COLS / BOX_W);
+
+        (0..N_ROWS).map(Unit::Row)
+            .chain((0..N_COLS).map(Unit::Column))
+            .chain((0..squares).map(Unit::Square))
+            .collect()
+    }
+
+    // the unit `mode` walks through when starting at (row, col), None for Affected and All
+    pub fn from_mode<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize>(row: usize, col: usize, mode: SudokuIteratorMode) -> Option<Unit>
+    {
+        // note that SudokuIteratorMode::Row walks down a column and Column walks along a row
+        match mode {
+            SudokuIteratorMode::Row => Some(Unit::Column(col)),
+            SudokuIteratorMode::Column => Some(Unit::Row(row)),
+            SudokuIteratorMode::Square => Some(Unit::Square((row / BOX_H) * (N_COLS / BOX_W) + col / BOX_W)),
+            SudokuIteratorMode::Affected | SudokuIteratorMode::All => None,
+        }
+    }
+
+    // the (row, col, mode) to hand to SudokuIterator::new for this unit
+    pub fn to_mode<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize>(&self) -> (usize, usize, SudokuIteratorMode)
+    {
+        match *self {
+            Unit::Row(row) => (row, 0, SudokuIteratorMode::Column),
+            Unit::Column(col) => (0, col, SudokuIteratorMode::Row),
+            Unit::Square(square) => ((square / (N_COLS / BOX_W)) * BOX_H, (square % (N_COLS / BOX_W)) * BOX_W, SudokuIteratorMode::Square),
+        }
+    }
+
+    pub fn contains<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize>(&self, row: usize, col: usize) -> bool
+    {
+        match *self {
+            Unit::Row(r) => r == row,
+            Unit::Column(c) => c == col,
+            Unit::Square(_) => Unit::from_mode::<N_ROWS, N_COLS, BOX_H, BOX_W>(row, col, SudokuIteratorMode::Square) == Some(*self),
+        }
+    }
+
+    pub fn iter<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize>(&self) -> SudokuIterator<N_ROWS, N_COLS, BOX_H, BOX_W>
+    {
+        let (row, col, mode) = self.to_mode::<N_ROWS, N_COLS, BOX_H, BOX_W>();
+
+        SudokuIterator::new(row, col, mode)
+    }
+}
+
+
 #[derive(Debug)]
-pub struct SudokuIterator<const N_ROWS: usize, const N_COLS: usize>
+pub struct SudokuIterator<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize = 3, const BOX_W: usize = 3>
 {
     row: usize,
     col: usize,
@@ -23,14 +89,41 @@ pub struct SudokuIterator<const N_ROWS: usize, const N_COLS: usize>
     sq_c: usize,
 
     mode: SudokuIteratorMode,
+
+    // the precomputed cells for the Affected mode, empty otherwise
+    affected: &'static [(usize, usize)],
 }
 
 
-impl<const N_ROWS: usize, const N_COLS: usize> SudokuIterator<N_ROWS, N_COLS> {
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize> SudokuIterator<N_ROWS, N_COLS, BOX_H, BOX_W> {
 
     pub fn new(row: usize, col: usize, mode: SudokuIteratorMode) -> Self {
 
-        SudokuIterator { row: row, col: col, c_r: 0, c_c: 0, sq_r: (row/3)*3, sq_c: (col/3)*3, mode: mode }
+        match mode {
+            SudokuIteratorMode::Affected => Self::with_geometry(Geometry::of::<N_ROWS, N_COLS, BOX_H, BOX_W>(), row, col, mode),
+            _ => SudokuIterator { row, col, c_r: 0, c_c: 0, sq_r: (row/BOX_H)*BOX_H, sq_c: (col/BOX_W)*BOX_W, mode, affected: &[] },
+        }
+    }
+
+    // like new, with the table for the Affected mode already at hand (see PencilNotes::geometry)
+    pub fn with_geometry(geometry: &'static Geometry, row: usize, col: usize, mode: SudokuIteratorMode) -> Self {
+
+        debug_assert!((geometry.n_rows, geometry.n_cols, geometry.box_h, geometry.box_w) == (N_ROWS, N_COLS, BOX_H, BOX_W), "the geometry is for another grid size");
+
+        let affected = match mode {
+            SudokuIteratorMode::Affected => geometry.affected(row, col),
+            _ => &[],
+        };
+
+        SudokuIterator { row, col, c_r: 0, c_c: 0, sq_r: (row/BOX_H)*BOX_H, sq_c: (col/BOX_W)*BOX_W, mode, affected }
+    }
+
+    // true if the two cells share a row, column or square. a cell doesn't see itself.
+    pub fn sees(row_a: usize, col_a: usize, row_b: usize, col_b: usize) -> bool
+    {
+        let same_square = row_a / BOX_H == row_b / BOX_H && col_a / BOX_W == col_b / BOX_W;
+
+        (row_a, col_a) != (row_b, col_b) && (row_a == row_b || col_a == col_b || same_square)
     }
 
     pub fn next_row(&mut self) -> Option<(usize, usize)>
@@ -63,7 +156,7 @@ impl<const N_ROWS: usize, const N_COLS: usize> SudokuIterator<N_ROWS, N_COLS> {
 
     pub fn next_square(&mut self) -> Option<(usize, usize)>
     {
-        if self.c_r >= 3
+        if self.c_r >= BOX_H
         {
             return None;
         }
@@ -71,7 +164,7 @@ impl<const N_ROWS: usize, const N_COLS: usize> SudokuIterator<N_ROWS, N_COLS> {
         let result = (self.sq_r + self.c_r, self.sq_c + self.c_c);
 
         self.c_c += 1;
-        if self.c_c >= 3
+        if self.c_c >= BOX_W
         {
             self.c_c = 0;
             self.c_r += 1;
@@ -82,64 +175,12 @@ impl<const N_ROWS: usize, const N_COLS: usize> SudokuIterator<N_ROWS, N_COLS> {
 
     pub fn next_affected(&mut self) -> Option<(usize, usize)>
     {
-        if self.c_r >= N_ROWS
-        {
-            // we're out of range
-            return None;
-        }
+        // c_c counts the cells handed out so far
+        let result = self.affected.get(self.c_c).copied();
 
-        let mut n_r = self.c_r;
-        let mut n_c = self.c_c;
-        let mut search = true;
-        
-        while N_ROWS > self.c_r && search
-        {
-            while N_COLS > self.c_c && search
-            {
-                // println!("c_r: {:?}, c_c: {:?}", self.c_r, self.c_c);
-                if self.sq_r <= self.c_r && self.sq_r + 3 > self.c_r &&
-                   self.sq_c <= self.c_c && self.sq_c + 3 > self.c_c
-                {
-                    n_r = self.c_r;
-                    n_c = self.c_c;
-
-                    search = false;
-                }
-                // check if we are in the current col or current row
-                else if self.c_r == self.row || self.c_c == self.col
-                {
-                    n_r = self.c_r;
-                    n_c = self.c_c;
-
-                    search = false;
-                }
-                // skip this cell
-                else
-                {
-                    search = true;
-                }
-
-                // increase column
-                self.c_c = self.c_c + 1;
-
-            }
-
-            if N_COLS <= self.c_c
-            {
-                self.c_c = 0;
-                self.c_r = self.c_r + 1;
-            }
-        }
-
-        if search
-        {
-            // we didn't find anything
-            return None;
-        }
-
-        // found the cell we need to return
-        Some((n_r, n_c))
+        self.c_c += 1;
 
+        result
     }
 
     pub fn next_all(&mut self) -> Option<(usize, usize)>
@@ -164,7 +205,7 @@ impl<const N_ROWS: usize, const N_COLS: usize> SudokuIterator<N_ROWS, N_COLS> {
 }
 
 
-impl<const N_ROWS: usize, const N_COLS: usize> Iterator for SudokuIterator<N_ROWS, N_COLS>
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize> Iterator for SudokuIterator<N_ROWS, N_COLS, BOX_H, BOX_W>
 {
     type Item = (usize, usize);
 
@@ -182,7 +223,7 @@ impl<const N_ROWS: usize, const N_COLS: usize> Iterator for SudokuIterator<N_ROW
     // this means, we'll descend row by row
     // if it's the row of the constructor, return all the indices
     // if it's the column of the constructor, return the column indice
-    // if the row and col are inside of the 3x3 square, return row & col
+    // if the row and col are inside of the BOX_H x BOX_W square, return row & col
 
     fn next(&mut self) -> Option<Self::Item> {
 
@@ -0,0 +1,412 @@
+use crate::sudoku::Sudoku;
+use crate::sudoku_candidate_storage::{CandidateStorage, DefaultStorage, StorageFor, Symbols};
+use crate::sudoku_iterator::{SudokuIterator, SudokuIteratorMode, Unit};
+use crate::sudoku_pencil_notes::{HiddenSingleIterator, PencilNotes};
+use crate::sudoku_pencil_notes_als::{AlsChain, AlsKind};
+use crate::sudoku_pencil_notes_aic::{Aic, AicKind, ForcingChain, ForcingKind};
+use crate::sudoku_pencil_notes_chains::{Chain, ChainKind};
+use crate::sudoku_pencil_notes_fish::Fish;
+use crate::sudoku_pencil_notes_uniqueness::{Uniqueness, UniquenessKind};
+use crate::sudoku_pencil_notes_wings::{Wing, WingKind};
+use crate::sudoku_solver::SolverState;
+
+
+// The techniques the logical solver knows, from the easiest to the hardest.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Technique
+{
+    NakedSingle,
+    HiddenSingle,
+    Pointing,
+    Claiming,
+    NakedPair,
+    XWing,
+    HiddenPair,
+    NakedTriple,
+    Swordfish,
+    HiddenTriple,
+    UniqueRectangle1,
+    UniqueRectangle2,
+    UniqueRectangle3,
+    UniqueRectangle4,
+    BugPlusOne,
+    Skyscraper,
+    TwoStringKite,
+    XYWing,
+    XYZWing,
+    WWing,
+    SimpleColoring,
+    FinnedXWing,
+    FinnedSwordfish,
+    NakedQuad,
+    Jellyfish,
+    HiddenQuad,
+    FinnedJellyfish,
+    XChain,
+    XYChain,
+    NiceLoop,
+    Aic,
+    AlsXZ,
+    AlsXYWing,
+    CellForcingChain,
+    UnitForcingChain,
+}
+
+
+// The cells and units behind a deduction, for techniques where the unit alone doesn't tell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pattern<S: CandidateStorage = u32>
+{
+    Fish(Fish),
+    Wing(Wing),
+    Chain(Chain),
+    Aic(Aic),
+    ForcingChain(ForcingChain),
+    Uniqueness(Uniqueness),
+    Als(AlsChain<S>),
+}
+
+
+// One deduction: either a number placed into a cell or a set of possibilities eliminated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step<S: CandidateStorage = u32>
+{
+    pub technique: Technique,
+    // the unit the technique was applied to, None if it is not bound to a single unit
+    pub unit: Option<Unit>,
+    // (row, col, number)
+    pub placement: Option<(usize, usize, u32)>,
+    // (row, col, number)
+    pub eliminations: Vec<(usize, usize, u32)>,
+    pub pattern: Option<Pattern<S>>,
+}
+
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LogicalOutcome
+{
+    Solved,
+    // no known technique applies anymore, continuing would need a guess
+    Stuck,
+    // the givens contradict each other
+    Contradiction,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct LogicalSolution<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize = 3, const BOX_W: usize = 3>
+where
+    Symbols<N_ROWS>: DefaultStorage,
+{
+    pub outcome: LogicalOutcome,
+    // the board as far as the solver got
+    pub sudoku: Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>,
+    pub pencil_notes: PencilNotes<N_ROWS, N_COLS, BOX_H, BOX_W, StorageFor<N_ROWS>>,
+    pub steps: Vec<Step<StorageFor<N_ROWS>>>,
+}
+
+
+// Solves a sudoku the way a player would: it always applies the easiest technique that
+// makes progress and never guesses. The pencil notes use the storage that fits the grid.
+pub struct LogicalSolver<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize = 3, const BOX_W: usize = 3>
+where
+    Symbols<N_ROWS>: DefaultStorage,
+{
+    state: SolverState<N_ROWS, N_COLS, BOX_H, BOX_W, StorageFor<N_ROWS>>,
+    // the longest chain (in links) the chain and forcing chain techniques look for
+    pub max_chain_length: usize,
+    // allows the techniques that rely on the puzzle having a single solution
+    pub assume_uniqueness: bool,
+}
+
+
+const DEFAULT_MAX_CHAIN_LENGTH: usize = 12;
+
+
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize> LogicalSolver<N_ROWS, N_COLS, BOX_H, BOX_W>
+where
+    Symbols<N_ROWS>: DefaultStorage,
+{
+
+    pub fn new(sudoku: &Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>) -> Self
+    {
+        // a given that is not a number of the grid leaves no possibilities at all,
+        // which the solver reports as a contradiction
+        let state = SolverState::new(sudoku).unwrap_or_else(|| {
+            let mut state = SolverState::empty();
+            state.sudoku = *sudoku;
+            state.pencil_notes.clear();
+            state
+        });
+
+        LogicalSolver { state, max_chain_length: DEFAULT_MAX_CHAIN_LENGTH, assume_uniqueness: false }
+    }
+
+    pub fn sudoku(&self) -> &Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>
+    {
+        &self.state.sudoku
+    }
+
+    pub fn pencil_notes(&self) -> &PencilNotes<N_ROWS, N_COLS, BOX_H, BOX_W, StorageFor<N_ROWS>>
+    {
+        &self.state.pencil_notes
+    }
+
+    // applies the easiest technique that makes progress, None if there is none
+    pub fn step(&mut self) -> Option<Step<StorageFor<N_ROWS>>>
+    {
+        if !self.state.is_consistent() {
+            return None;
+        }
+
+        self.naked_single()
+            .or_else(|| self.hidden_single())
+            .or_else(|| self.unit_technique(Technique::Pointing))
+            .or_else(|| self.unit_technique(Technique::Claiming))
+            .or_else(|| self.unit_technique(Technique::NakedPair))
+            .or_else(|| self.fish(Technique::XWing))
+            .or_else(|| self.unit_technique(Technique::HiddenPair))
+            .or_else(|| self.unit_technique(Technique::NakedTriple))
+            .or_else(|| self.fish(Technique::Swordfish))
+            .or_else(|| self.unit_technique(Technique::HiddenTriple))
+            .or_else(|| self.uniqueness(Technique::UniqueRectangle1))
+            .or_else(|| self.uniqueness(Technique::UniqueRectangle2))
+            .or_else(|| self.uniqueness(Technique::UniqueRectangle3))
+            .or_else(|| self.uniqueness(Technique::UniqueRectangle4))
+            .or_else(|| self.uniqueness(Technique::BugPlusOne))
+            .or_else(|| self.chain(Technique::Skyscraper))
+            .or_else(|| self.chain(Technique::TwoStringKite))
+            .or_else(|| self.wing(Technique::XYWing))
+            .or_else(|| self.wing(Technique::XYZWing))
+            .or_else(|| self.wing(Technique::WWing))
+            .or_else(|| self.chain(Technique::SimpleColoring))
+            .or_else(|| self.fish(Technique::FinnedXWing))
+            .or_else(|| self.fish(Technique::FinnedSwordfish))
+            .or_else(|| self.unit_technique(Technique::NakedQuad))
+            .or_else(|| self.fish(Technique::Jellyfish))
+            .or_else(|| self.unit_technique(Technique::HiddenQuad))
+            .or_else(|| self.fish(Technique::FinnedJellyfish))
+            .or_else(|| self.chain(Technique::XChain))
+            .or_else(|| self.aic(Technique::XYChain))
+            .or_else(|| self.aic(Technique::NiceLoop))
+            .or_else(|| self.aic(Technique::Aic))
+            .or_else(|| self.als(Technique::AlsXZ))
+            .or_else(|| self.als(Technique::AlsXYWing))
+            .or_else(|| self.forcing_chain(Technique::CellForcingChain))
+            .or_else(|| self.forcing_chain(Technique::UnitForcingChain))
+    }
+
+    pub fn solve(&mut self) -> LogicalSolution<N_ROWS, N_COLS, BOX_H, BOX_W>
+    {
+        let mut steps = Vec::new();
+
+        while let Some(step) = self.step() {
+            steps.push(step);
+        }
+
+        let outcome = if !self.state.is_consistent() {
+            LogicalOutcome::Contradiction
+        } else if self.state.sudoku.is_complete() {
+            LogicalOutcome::Solved
+        } else {
+            LogicalOutcome::Stuck
+        };
+
+        LogicalSolution { outcome, sudoku: self.state.sudoku, pencil_notes: self.state.pencil_notes.clone(), steps }
+    }
+
+    fn naked_single(&mut self) -> Option<Step<StorageFor<N_ROWS>>>
+    {
+        for (r, c) in SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(0, 0, SudokuIteratorMode::All) {
+
+            if self.state.sudoku.board[r][c] != 0 {
+                continue;
+            }
+
+            if let Some(number) = self.state.pencil_notes.get_possibility(r, c) {
+
+                self.state.place(r, c, number);
+
+                return Some(Step { technique: Technique::NakedSingle, unit: None, placement: Some((r, c, number)), eliminations: Vec::new(), pattern: None });
+            }
+        }
+
+        None
+    }
+
+    fn hidden_single(&mut self) -> Option<Step<StorageFor<N_ROWS>>>
+    {
+        for unit in Unit::all::<N_ROWS, N_COLS, BOX_H, BOX_W>() {
+
+            let (row, col, mode) = unit.to_mode::<N_ROWS, N_COLS, BOX_H, BOX_W>();
+            for (r, c, number) in HiddenSingleIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(&self.state.pencil_notes, row, col, mode) {
+
+                if self.state.sudoku.board[r][c] == 0 {
+
+                    self.state.place(r, c, number);
+
+                    return Some(Step { technique: Technique::HiddenSingle, unit: Some(unit), placement: Some((r, c, number)), eliminations: Vec::new(), pattern: None });
+                }
+            }
+        }
+
+        None
+    }
+
+    // techniques that work on one unit at a time and only eliminate possibilities
+    fn unit_technique(&mut self, technique: Technique) -> Option<Step<StorageFor<N_ROWS>>>
+    {
+        for unit in Unit::all::<N_ROWS, N_COLS, BOX_H, BOX_W>() {
+
+            let (row, col, mode) = unit.to_mode::<N_ROWS, N_COLS, BOX_H, BOX_W>();
+            let pencil_notes = &mut self.state.pencil_notes;
+
+            let eliminations = match (technique, unit) {
+                (Technique::Pointing, Unit::Square(_)) => pencil_notes.handle_pointing(row, col),
+                (Technique::Claiming, Unit::Row(_) | Unit::Column(_)) => pencil_notes.handle_claiming(row, col, mode),
+                (Technique::NakedPair, _) => pencil_notes.handle_naked_subsets(row, col, mode, 2),
+                (Technique::HiddenPair, _) => pencil_notes.handle_hidden_subsets(row, col, mode, 2),
+                (Technique::NakedTriple, _) => pencil_notes.handle_naked_subsets(row, col, mode, 3),
+                (Technique::HiddenTriple, _) => pencil_notes.handle_hidden_subsets(row, col, mode, 3),
+                (Technique::NakedQuad, _) => pencil_notes.handle_naked_subsets(row, col, mode, 4),
+                (Technique::HiddenQuad, _) => pencil_notes.handle_hidden_subsets(row, col, mode, 4),
+                _ => continue,
+            };
+
+            if !eliminations.is_empty() {
+                return Some(Step { technique, unit: Some(unit), placement: None, eliminations, pattern: None });
+            }
+        }
+
+        None
+    }
+
+    fn fish(&mut self, technique: Technique) -> Option<Step<StorageFor<N_ROWS>>>
+    {
+        let (size, finned) = match technique {
+            Technique::XWing => (2, false),
+            Technique::Swordfish => (3, false),
+            Technique::Jellyfish => (4, false),
+            Technique::FinnedXWing => (2, true),
+            Technique::FinnedSwordfish => (3, true),
+            Technique::FinnedJellyfish => (4, true),
+            _ => return None,
+        };
+
+        let fish = self.state.pencil_notes.handle_fish(size, finned)?;
+
+        Some(Step { technique, unit: None, placement: None, eliminations: fish.eliminations.clone(), pattern: Some(Pattern::Fish(fish)) })
+    }
+
+    fn wing(&mut self, technique: Technique) -> Option<Step<StorageFor<N_ROWS>>>
+    {
+        let kind = match technique {
+            Technique::XYWing => WingKind::XYWing,
+            Technique::XYZWing => WingKind::XYZWing,
+            Technique::WWing => WingKind::WWing,
+            _ => return None,
+        };
+
+        let wing = self.state.pencil_notes.handle_wing(kind)?;
+
+        Some(Step { technique, unit: None, placement: None, eliminations: wing.eliminations.clone(), pattern: Some(Pattern::Wing(wing)) })
+    }
+
+    fn chain(&mut self, technique: Technique) -> Option<Step<StorageFor<N_ROWS>>>
+    {
+        let kind = match technique {
+            Technique::SimpleColoring => ChainKind::SimpleColoring,
+            Technique::Skyscraper => ChainKind::Skyscraper,
+            Technique::TwoStringKite => ChainKind::TwoStringKite,
+            Technique::XChain => ChainKind::XChain,
+            _ => return None,
+        };
+
+        let chain = self.state.pencil_notes.handle_chain(kind)?;
+
+        Some(Step { technique, unit: None, placement: None, eliminations: chain.eliminations.clone(), pattern: Some(Pattern::Chain(chain)) })
+    }
+
+    fn aic(&mut self, technique: Technique) -> Option<Step<StorageFor<N_ROWS>>>
+    {
+        let kind = match technique {
+            Technique::XYChain => AicKind::XYChain,
+            Technique::NiceLoop => AicKind::NiceLoop,
+            Technique::Aic => AicKind::Aic,
+            _ => return None,
+        };
+
+        let aic = self.state.pencil_notes.handle_aic(kind, self.max_chain_length)?;
+
+        Some(Step { technique, unit: None, placement: None, eliminations: aic.eliminations.clone(), pattern: Some(Pattern::Aic(aic)) })
+    }
+
+    fn als(&mut self, technique: Technique) -> Option<Step<StorageFor<N_ROWS>>>
+    {
+        let kind = match technique {
+            Technique::AlsXZ => AlsKind::AlsXZ,
+            Technique::AlsXYWing => AlsKind::AlsXYWing,
+            _ => return None,
+        };
+
+        let chain = self.state.pencil_notes.handle_als_chain(kind)?;
+
+        Some(Step { technique, unit: None, placement: None, eliminations: chain.eliminations.clone(), pattern: Some(Pattern::Als(chain)) })
+    }
+
+    fn forcing_chain(&mut self, technique: Technique) -> Option<Step<StorageFor<N_ROWS>>>
+    {
+        let kind = match technique {
+            Technique::CellForcingChain => ForcingKind::Cell,
+            Technique::UnitForcingChain => ForcingKind::Unit,
+            _ => return None,
+        };
+
+        let forcing_chain = self.state.pencil_notes.find_forcing_chain(kind, self.max_chain_length)?;
+
+        if let Some((r, c, number)) = forcing_chain.placement {
+            self.state.place(r, c, number);
+        }
+        for &(r, c, number) in &forcing_chain.eliminations {
+            self.state.pencil_notes.remove_possibility(r, c, number);
+        }
+
+        Some(Step {
+            technique,
+            unit: forcing_chain.unit,
+            placement: forcing_chain.placement,
+            eliminations: forcing_chain.eliminations.clone(),
+            pattern: Some(Pattern::ForcingChain(forcing_chain)),
+        })
+    }
+
+    fn uniqueness(&mut self, technique: Technique) -> Option<Step<StorageFor<N_ROWS>>>
+    {
+        if !self.assume_uniqueness {
+            return None;
+        }
+
+        let kind = match technique {
+            Technique::UniqueRectangle1 => UniquenessKind::UniqueRectangle1,
+            Technique::UniqueRectangle2 => UniquenessKind::UniqueRectangle2,
+            Technique::UniqueRectangle3 => UniquenessKind::UniqueRectangle3,
+            Technique::UniqueRectangle4 => UniquenessKind::UniqueRectangle4,
+            Technique::BugPlusOne => UniquenessKind::BugPlusOne,
+            _ => return None,
+        };
+
+        let uniqueness = self.state.pencil_notes.handle_uniqueness(kind)?;
+
+        if let Some((r, c, number)) = uniqueness.placement {
+            self.state.place(r, c, number);
+        }
+
+        Some(Step {
+            technique,
+            unit: None,
+            placement: uniqueness.placement,
+            eliminations: uniqueness.eliminations.clone(),
+            pattern: Some(Pattern::Uniqueness(uniqueness)),
+        })
+    }
+}
@@ -0,0 +1,79 @@
+use crate::sudoku::Sudoku;
+use crate::sudoku_candidate_storage::{CandidateStorage, StorageFor};
+use crate::sudoku_iterator::SudokuIteratorMode;
+use crate::sudoku_pencil_notes::PencilNotes;
+
+
+// Callbacks for following SudokuFactory::generate step by step.
+// All methods default to doing nothing, so an observer only implements what it needs.
+// S is the storage of the pencil notes, by default the one the factory picks for the grid size.
+pub trait GenerationObserver<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize = 3, const BOX_W: usize = 3, S: CandidateStorage = StorageFor<N_ROWS>>
+{
+    fn naked_single(&mut self, _iteration: usize, _row: usize, _col: usize, _number: u32) {}
+
+    // mode is the unit the number was hidden in, as passed to HiddenSingleIterator
+    fn hidden_single(&mut self, _iteration: usize, _row: usize, _col: usize, _number: u32, _mode: SudokuIteratorMode) {}
+
+    fn pair_elimination(&mut self, _iteration: usize, _row: usize, _col: usize, _number: u32) {}
+
+    fn random_guess(&mut self, _iteration: usize, _row: usize, _col: usize, _number: u32) {}
+
+    // the board hit a contradiction and the last random guess is taken back
+    fn backtrack(&mut self, _iteration: usize, _sudoku: &Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>) {}
+
+    // called once at the end of every iteration
+    fn snapshot(&mut self, _iteration: usize, _sudoku: &Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>, _pencil_notes: &PencilNotes<N_ROWS, N_COLS, BOX_H, BOX_W, S>) {}
+}
+
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SilentObserver;
+
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize, S: CandidateStorage> GenerationObserver<N_ROWS, N_COLS, BOX_H, BOX_W, S> for SilentObserver {}
+
+
+// prints every step of the generation to stdout
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StdoutObserver;
+
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize, S: CandidateStorage> GenerationObserver<N_ROWS, N_COLS, BOX_H, BOX_W, S> for StdoutObserver {
+
+    fn naked_single(&mut self, iteration: usize, row: usize, col: usize, number: u32) {
+
+        println!("{}: Naked single found at ({}, {}) with possibility {}", iteration, row, col, number);
+    }
+
+    fn hidden_single(&mut self, iteration: usize, row: usize, col: usize, number: u32, mode: SudokuIteratorMode) {
+
+        let unit = match mode {
+            SudokuIteratorMode::Square => "square",
+            SudokuIteratorMode::Column => "column",
+            SudokuIteratorMode::Row => "row",
+            SudokuIteratorMode::Affected => "affected",
+            SudokuIteratorMode::All => "board",
+        };
+
+        println!("{}: Hidden single {} found at ({}, {}) with possibility {}", iteration, unit, row, col, number);
+    }
+
+    fn pair_elimination(&mut self, iteration: usize, row: usize, col: usize, number: u32) {
+
+        println!("{}: Eliminating pair possibility {} at ({}, {})", iteration, number, row, col);
+    }
+
+    fn random_guess(&mut self, iteration: usize, row: usize, col: usize, number: u32) {
+
+        println!("{}: Filling cell ({}, {}) with random number {}", iteration, row, col, number);
+    }
+
+    fn backtrack(&mut self, _iteration: usize, sudoku: &Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>) {
+
+        println!("Sudoku state is invalid, backtracking!\n{}", sudoku);
+    }
+
+    fn snapshot(&mut self, _iteration: usize, sudoku: &Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>, pencil_notes: &PencilNotes<N_ROWS, N_COLS, BOX_H, BOX_W, S>) {
+
+        println!("\nCurrent Sudoku State:\n{}", sudoku);
+        println!("Current Pencil Notes State:\n{}", pencil_notes);
+    }
+}
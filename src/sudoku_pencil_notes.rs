@@ -1,46 +1,89 @@
 use rand::{Rng, RngCore};
+use rand::seq::SliceRandom;
 
-use crate::sudoku_iterator::{SudokuIterator, SudokuIteratorMode};
+use crate::sudoku::Sudoku;
+use crate::sudoku_cell::{CellPos, Digit};
+use crate::sudoku_error::SudokuError;
+use crate::sudoku_candidate_storage::{CandidateStorage, StorageFor};
+use crate::sudoku_candidates::Candidates;
+use crate::sudoku_geometry::Geometry;
+use crate::sudoku_iterator::{SudokuIterator, SudokuIteratorMode, Unit};
 
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct PencilNotes<const N_ROWS: usize, const N_COLS: usize>
+pub struct PencilNotes<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize = 3, const BOX_W: usize = 3, S: CandidateStorage = StorageFor<N_ROWS>>
 {
-    pub possibilities: [[u32; N_COLS]; N_ROWS],
+    pub possibilities: [[S; N_COLS]; N_ROWS],
+    // looked up once here, Geometry::of takes a lock
+    geometry: &'static Geometry,
 }
 
 
-impl<const N_ROWS: usize, const N_COLS: usize> PencilNotes<N_ROWS, N_COLS> {
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize, S: CandidateStorage> PencilNotes<N_ROWS, N_COLS, BOX_H, BOX_W, S> {
 
     const CHECK_CONSTRAINTS: () = {
         // Constraint 1: Minimum Size
-        assert!(N_ROWS > 3, "N_ROWS must be greater than 3");
-        assert!(N_COLS > 3, "N_COLS must be greater than 3");
+        assert!(BOX_H > 1, "BOX_H must be greater than 1");
+        assert!(BOX_W > 1, "BOX_W must be greater than 1");
 
-        // Constraint 2: Integer Size
-        let block_h = N_ROWS / 3;
-        let block_w = N_COLS / 3;
-        let symbols_needed = block_h * block_w;
+        // Constraint 2: Box Dimensions
+        // every row, column and box holds each number once, so all of them have BOX_H * BOX_W cells
+        assert!(N_ROWS == BOX_H * BOX_W, "N_ROWS must be BOX_H * BOX_W");
+        assert!(N_COLS == BOX_H * BOX_W, "N_COLS must be BOX_H * BOX_W");
+
+        // Constraint 3: Integer Size
+        let symbols_needed = BOX_H * BOX_W;
 
         assert!(
-            symbols_needed <= u32::BITS as usize, 
-            "The chosen type u32 is too small for this grid size!"
+            symbols_needed <= S::BITS as usize,
+            "The chosen candidate storage is too small for this grid size!"
         );
     };
 
     pub fn new() -> Self
     {
-        let _ = Self::CHECK_CONSTRAINTS;
+        let () = Self::CHECK_CONSTRAINTS;
 
-        let number_of_symbols_mask = ((1 as u32) << ((N_ROWS / 3) * (N_COLS / 3))) - 1;
+        let number_of_symbols_mask = S::lowest((BOX_H * BOX_W) as u32);
 
-        PencilNotes { possibilities: [[number_of_symbols_mask; N_COLS]; N_ROWS] }
+        PencilNotes { possibilities: [[number_of_symbols_mask; N_COLS]; N_ROWS], geometry: Geometry::of::<N_ROWS, N_COLS, BOX_H, BOX_W>() }
+    }
+
+    // the units and peers of this grid size
+    pub fn geometry(&self) -> &'static Geometry
+    {
+        self.geometry
+    }
+
+    // a given outside 1..=BOX_H * BOX_W leaves its cell without candidates, see try_from_sudoku
+    pub fn from_sudoku(sudoku: &Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>) -> Self
+    {
+        let mut pencil_notes = Self::new();
+
+        for (r, c) in SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(0, 0, SudokuIteratorMode::All) {
+
+            let number = sudoku.board[r][c];
+            if number != 0 {
+                pencil_notes.set_possibility(r, c, number);
+                pencil_notes.eliminate_possibility(r, c, number);
+            }
+        }
+
+        pencil_notes
+    }
+
+    // from_sudoku with every given checked first
+    pub fn try_from_sudoku(sudoku: &Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>) -> Result<Self, SudokuError>
+    {
+        let sudoku = Sudoku::try_new(sudoku.board)?;
+
+        Ok(Self::from_sudoku(&sudoku))
     }
 
     pub fn check(&self) -> bool {
         for r in 0..N_ROWS {
             for c in 0..N_COLS {
-                if self.possibilities[r][c] == 0
+                if self.possibilities[r][c].is_empty()
                 {
                     return false;
                 }
@@ -51,7 +94,7 @@ impl<const N_ROWS: usize, const N_COLS: usize> PencilNotes<N_ROWS, N_COLS> {
 
     pub fn reset(&mut self) {
 
-        let number_of_symbols_mask = ((1 as u32) << ((N_ROWS / 3) * (N_COLS / 3))) - 1;
+        let number_of_symbols_mask = S::lowest((BOX_H * BOX_W) as u32);
 
         for r in 0..N_ROWS {
             for c in 0..N_COLS {
@@ -64,37 +107,73 @@ impl<const N_ROWS: usize, const N_COLS: usize> PencilNotes<N_ROWS, N_COLS> {
 
         for r in 0..N_ROWS {
             for c in 0..N_COLS {
-                self.possibilities[r][c] = 0;
+                self.possibilities[r][c] = S::EMPTY;
             }
         }
     }
 
-    pub fn add_possibility(&mut self, row: usize, col: usize, number: u32) {
+    // unchecked, outside the crate see try_add_possibility
+    pub(crate) fn add_possibility(&mut self, row: usize, col: usize, number: u32) {
 
-        let mask = 1 << (number - 1);
-        self.possibilities[row][col] |= mask;
+        let mut candidates = self.get_possibilities(row, col);
+        candidates.insert(number);
+        self.possibilities[row][col] = candidates.bits();
     }
 
+    // does nothing for a cell or a number outside the grid
     pub fn remove_possibility(&mut self, row: usize, col: usize, number: u32) {
 
-        let mask = 1 << (number - 1);
-        self.possibilities[row][col] &= !mask;
+        if let Some(bits) = self.possibilities.get_mut(row).and_then(|cells| cells.get_mut(col)) {
+            let mut candidates = Candidates::from_bits(*bits);
+            candidates.remove(number);
+            *bits = candidates.bits();
+        }
+    }
+
+    // add_possibility with the coordinate and the number checked first
+    pub fn try_add_possibility(&mut self, row: usize, col: usize, number: u32) -> Result<(), SudokuError> {
+
+        let (pos, digit) = Self::checked(row, col, number)?;
+        self.add_possibility(pos.row(), pos.col(), digit.get());
+
+        Ok(())
     }
 
-    pub fn set_possibility(&mut self, row: usize, col: usize, number: u32) {
+    pub fn try_remove_possibility(&mut self, row: usize, col: usize, number: u32) -> Result<(), SudokuError> {
+
+        let (pos, digit) = Self::checked(row, col, number)?;
+        self.remove_possibility(pos.row(), pos.col(), digit.get());
+
+        Ok(())
+    }
+
+    pub fn try_set_possibility(&mut self, row: usize, col: usize, number: u32) -> Result<(), SudokuError> {
+
+        let (pos, digit) = Self::checked(row, col, number)?;
+        self.set_possibility(pos.row(), pos.col(), digit.get());
 
-        let mask = 1 << (number - 1);
-        self.possibilities[row][col] = mask;
+        Ok(())
+    }
+
+    fn checked(row: usize, col: usize, number: u32) -> Result<(CellPos<N_ROWS, N_COLS, BOX_H, BOX_W>, Digit<N_ROWS, N_COLS, BOX_H, BOX_W>), SudokuError> {
+
+        Ok((CellPos::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(row, col)?, Digit::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(number)?))
+    }
+
+    // unchecked, outside the crate see try_set_possibility
+    pub(crate) fn set_possibility(&mut self, row: usize, col: usize, number: u32) {
+
+        self.possibilities[row][col] = Candidates::<S>::from_numbers(&[number]).bits();
     }
 
     pub fn clear_possibilities(&mut self, row: usize, col: usize) {
 
-        self.possibilities[row][col] = 0;
+        self.possibilities[row][col] = S::EMPTY;
     }
 
     pub fn eliminate_possibility_row(&mut self, row: usize, col: usize, number: u32) {
 
-        for (r, c) in SudokuIterator::<N_ROWS, N_COLS>::new(row, col, SudokuIteratorMode::Row) {
+        for (r, c) in SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(row, col, SudokuIteratorMode::Row) {
 
             if r == row && c == col {
                 continue;
@@ -106,7 +185,7 @@ impl<const N_ROWS: usize, const N_COLS: usize> PencilNotes<N_ROWS, N_COLS> {
 
     pub fn eliminate_possibility_col(&mut self, row: usize, col: usize, number: u32) {
 
-        for (r, c) in SudokuIterator::<N_ROWS, N_COLS>::new(row, col, SudokuIteratorMode::Column) {
+        for (r, c) in SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(row, col, SudokuIteratorMode::Column) {
 
             if r == row && c == col {
                 continue;
@@ -118,7 +197,7 @@ impl<const N_ROWS: usize, const N_COLS: usize> PencilNotes<N_ROWS, N_COLS> {
 
     pub fn eliminate_possibility_square(&mut self, row: usize, col: usize, number: u32) {
 
-        for (r, c) in SudokuIterator::<N_ROWS, N_COLS>::new(row, col, SudokuIteratorMode::Square) {
+        for (r, c) in SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(row, col, SudokuIteratorMode::Square) {
 
             if r == row && c == col {
                 continue;
@@ -130,12 +209,7 @@ impl<const N_ROWS: usize, const N_COLS: usize> PencilNotes<N_ROWS, N_COLS> {
 
     pub fn eliminate_possibility(&mut self, row: usize, col: usize, number: u32) {
 
-        for (r, c) in SudokuIterator::<N_ROWS, N_COLS>::new(row, col, SudokuIteratorMode::Affected) {
-
-            if r == row && c == col {
-                continue;
-            }
-
+        for &(r, c) in self.geometry.peers(row, col) {
             self.remove_possibility(r, c, number);
         }
     }
@@ -147,26 +221,21 @@ impl<const N_ROWS: usize, const N_COLS: usize> PencilNotes<N_ROWS, N_COLS> {
         self.eliminate_possibility_square(row, col, number);
     }
 
-    pub fn get_possibilities(&self, row: usize, col: usize) -> u32 {
+    pub fn get_possibilities(&self, row: usize, col: usize) -> Candidates<S> {
 
-        self.possibilities[row][col]
+        Candidates::from_bits(self.possibilities[row][col])
     }
 
     pub fn get_possibility(&self, row: usize, col: usize) -> Option<u32> {
 
-        if 1 == self.count_possibilities(row, col) {
-            let trailing = self.possibilities[row][col].trailing_zeros();
-
-            Some(trailing + 1)
-        } else {
-            None
-        }
+        self.get_possibilities(row, col).single()
     }
 
+    // false for a cell or a number outside the grid
     pub fn has_possibility(&self, row: usize, col: usize, number: u32) -> bool {
-        
-        let mask = 1 << (number - 1);
-        (self.possibilities[row][col] & mask) != 0
+
+        self.possibilities.get(row).and_then(|cells| cells.get(col))
+            .is_some_and(|&bits| Candidates::from_bits(bits).contains(number))
     }
 
     pub fn is_possible(&self, row: usize, col: usize, number: u32) -> bool {
@@ -176,10 +245,9 @@ impl<const N_ROWS: usize, const N_COLS: usize> PencilNotes<N_ROWS, N_COLS> {
             good = false;
         }
         
-        let mask = 1u32 << (number -1);
-        for (row, col) in SudokuIterator::<N_ROWS, N_COLS>::new(row, col, SudokuIteratorMode::Affected)
+        for &(row, col) in self.geometry.affected(row, col)
         {
-            if self.possibilities[row][col] & mask == mask {
+            if self.has_possibility(row, col, number) {
                 good = false;
                 break;
             }
@@ -231,11 +299,22 @@ impl<const N_ROWS: usize, const N_COLS: usize> PencilNotes<N_ROWS, N_COLS> {
         best_pos
     }
 
+    // (row, col, number) for every cell with the number that sees all the given cells
+    pub fn seeing_all(&self, cells: &[(usize, usize)], number: u32) -> Vec<(usize, usize, u32)> {
+
+        let (first_r, first_c) = cells[0];
+
+        self.geometry.peers(first_r, first_c).iter().copied()
+            .filter(|&(r, c)| self.has_possibility(r, c, number))
+            .filter(|&(r, c)| cells.iter().all(|&(cell_r, cell_c)| SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::sees(r, c, cell_r, cell_c)))
+            .map(|(r, c)| (r, c, number))
+            .collect()
+    }
+
     pub fn find_highest_entropy_cell(&self) -> Option<(usize, usize)> {
 
         let mut max_count = 0u32;
         let mut best_pos = None;
-        let max_number:usize = N_ROWS/3 * N_COLS/3;
 
         for r in 0..N_ROWS {
             for c in 0..N_COLS {
@@ -270,138 +349,263 @@ impl<const N_ROWS: usize, const N_COLS: usize> PencilNotes<N_ROWS, N_COLS> {
     }
 
 
-    pub fn handle_naked_pairs(&mut self, row: usize, col: usize, mode: SudokuIteratorMode) {
-        
-        for (row_idx_a, col_idx_a) in SudokuIterator::<N_ROWS, N_COLS>::new(row, col, mode) {
+    // pointing: if a number's places in the square of (row, col) all lie in one row or column,
+    // the number can't go anywhere else in that row or column.
+    // returns the (row, col, number) possibilities that were eliminated
+    pub fn handle_pointing(&mut self, row: usize, col: usize) -> Vec<(usize, usize, u32)> {
+
+        let max_number = (BOX_H * BOX_W) as u32;
+        let square = Unit::from_mode::<N_ROWS, N_COLS, BOX_H, BOX_W>(row, col, SudokuIteratorMode::Square).unwrap();
+        let mut eliminated = Vec::new();
 
-            if self.count_possibilities(row_idx_a, col_idx_a) != 2 {
+        for number in 1..=max_number {
+
+            let places: Vec<(usize, usize)> = SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(row, col, SudokuIteratorMode::Square)
+                .filter(|&(r, c)| self.has_possibility(r, c, number))
+                .collect();
+
+            // a single place is a hidden single, not a pointing pair
+            if places.len() < 2 {
                 continue;
             }
-            
-            for (row_idx_b, col_idx_b) in SudokuIterator::<N_ROWS, N_COLS>::new(row, col, mode) {
 
-                if row_idx_a == row_idx_b && col_idx_a == col_idx_b {
-                    continue;
+            let (first_r, first_c) = places[0];
+
+            let line = if places.iter().all(|&(r, _)| r == first_r) {
+                SudokuIteratorMode::Column // walks along row first_r
+            } else if places.iter().all(|&(_, c)| c == first_c) {
+                SudokuIteratorMode::Row // walks down column first_c
+            } else {
+                continue;
+            };
+
+            for (r, c) in SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(first_r, first_c, line) {
+
+                if !square.contains::<N_ROWS, N_COLS, BOX_H, BOX_W>(r, c) && self.has_possibility(r, c, number) {
+                    self.remove_possibility(r, c, number);
+                    eliminated.push((r, c, number));
                 }
+            }
+        }
 
-                if self.possibilities[row_idx_a][col_idx_a] == self.possibilities[row_idx_b][col_idx_b] {
+        eliminated
+    }
 
-                    // Found a naked pair
-                    let pair_mask = self.possibilities[row_idx_a][col_idx_a];
+    // claiming (box-line reduction): if a number's places in the row or column given by
+    // (row, col, mode) all lie in one square, the number can't go anywhere else in that square.
+    // returns the (row, col, number) possibilities that were eliminated
+    pub fn handle_claiming(&mut self, row: usize, col: usize, mode: SudokuIteratorMode) -> Vec<(usize, usize, u32)> {
 
-                    // println!("Found naked pair at ({}, {}) and ({}, {}) with mask {:09b}", row_idx_a, col_idx_a, row_idx_b, col_idx_b, pair_mask);
+        let max_number = (BOX_H * BOX_W) as u32;
+        let mut eliminated = Vec::new();
 
-                    // eliminate these two possibilities from other affected cells in the unit
-                    for (r, c) in SudokuIterator::<N_ROWS, N_COLS>::new(row, col, mode) {
+        let Some(line) = Unit::from_mode::<N_ROWS, N_COLS, BOX_H, BOX_W>(row, col, mode) else {
+            return eliminated;
+        };
 
-                        if (r == row_idx_a && c == col_idx_a) || (r == row_idx_b && c == col_idx_b) {
-                            continue;
-                        }
+        for number in 1..=max_number {
 
-                        let before = self.possibilities[r][c];
-                        if before & pair_mask != 0 {
+            let places: Vec<(usize, usize)> = SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(row, col, mode)
+                .filter(|&(r, c)| self.has_possibility(r, c, number))
+                .collect();
 
-                            let after = before & (!pair_mask);
+            if places.len() < 2 {
+                continue;
+            }
 
-                            // println!("Eliminating naked pair possibilities at ({}, {}): {:09b} & {:09b} -> {:09b}", r, c, before, pair_mask, after);
+            let (first_r, first_c) = places[0];
+            let square = Unit::from_mode::<N_ROWS, N_COLS, BOX_H, BOX_W>(first_r, first_c, SudokuIteratorMode::Square).unwrap();
 
-                            self.possibilities[r][c] = after;
+            if !places.iter().all(|&(r, c)| square.contains::<N_ROWS, N_COLS, BOX_H, BOX_W>(r, c)) {
+                continue;
+            }
 
-                            // println!("Eliminating naked pair possibilities at ({}, {}): {:09b} & {:09b} -> {:09b}", r, c, before, pair_mask, after);
-                        }
+            for (r, c) in SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(first_r, first_c, SudokuIteratorMode::Square) {
+
+                if !line.contains::<N_ROWS, N_COLS, BOX_H, BOX_W>(r, c) && self.has_possibility(r, c, number) {
+                    self.remove_possibility(r, c, number);
+                    eliminated.push((r, c, number));
+                }
+            }
+        }
+
+        eliminated
+    }
+
+    pub fn handle_naked_pairs(&mut self, row: usize, col: usize, mode: SudokuIteratorMode) -> Vec<(usize, usize, u32)> {
+
+        self.handle_naked_subsets(row, col, mode, 2)
+    }
+
+    pub fn handle_hidden_pairs(&mut self, row: usize, col: usize, mode: SudokuIteratorMode) -> Vec<(usize, usize, u32)> {
+
+        self.handle_hidden_subsets(row, col, mode, 2)
+    }
+
+    // naked subset: `size` cells of the unit that together hold exactly `size` numbers.
+    // those numbers can't go anywhere else in the unit.
+    // returns the (row, col, number) possibilities that were eliminated
+    pub fn handle_naked_subsets(&mut self, row: usize, col: usize, mode: SudokuIteratorMode, size: usize) -> Vec<(usize, usize, u32)> {
+
+        let mut eliminated = Vec::new();
+
+        let unit: Vec<(usize, usize)> = SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(row, col, mode).collect();
+
+        // cells with a single possibility are solved (or a naked single), everything above size can't take part
+        let cells: Vec<(usize, usize)> = unit.iter()
+            .copied()
+            .filter(|&(r, c)| (2..=size).contains(&(self.count_possibilities(r, c) as usize)))
+            .collect();
+
+        for combination in combinations(cells.len(), size) {
+
+            let subset: Vec<(usize, usize)> = combination.iter().map(|&index| cells[index]).collect();
+            let subset_mask = subset.iter().fold(S::EMPTY, |mask, &(r, c)| mask | self.possibilities[r][c]);
+
+            if subset_mask.count_ones() as usize != size {
+                continue;
+            }
+
+            // eliminate the subset's possibilities from the other cells in the unit
+            for &(r, c) in &unit {
+
+                if subset.contains(&(r, c)) {
+                    continue;
+                }
+
+                let before = self.possibilities[r][c];
+                if !(before & subset_mask).is_empty() {
+
+                    self.possibilities[r][c] = before & !subset_mask;
+
+                    for number in PossibilityIterator::new(before & subset_mask) {
+                        eliminated.push((r, c, number));
                     }
                 }
             }
         }
+
+        eliminated
     }
 
-    pub fn handle_hidden_pairs(&mut self, row: usize, col: usize, mode: SudokuIteratorMode) {
+    // hidden subset: `size` numbers that can only go into the same `size` cells of the unit.
+    // those cells can't take any other number.
+    // returns the (row, col, number) possibilities that were eliminated
+    pub fn handle_hidden_subsets(&mut self, row: usize, col: usize, mode: SudokuIteratorMode, size: usize) -> Vec<(usize, usize, u32)> {
 
-        let max_number:usize = N_ROWS/3 * N_COLS/3;
+        let max_number:usize = BOX_H * BOX_W;
+        let mut eliminated = Vec::new();
 
         // the index for places is the number 1-9 minus 1
         // it stores a bitmask for each place (0-max_number) where that number is a possibility...
-        let mut places: [u32; 32] = [0 as u32; 32];
+        // a unit has as many cells as there are numbers, so the storage fits the places as well
+        let mut places: Vec<S> = vec![S::EMPTY; max_number];
 
         // this stores the coordinates of each index of the iterator we check.
-        let mut coordinates: [(usize, usize); 32] = [(0, 0); 32];
+        let coordinates: Vec<(usize, usize)> = SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(row, col, mode).collect();
 
-        for (index, (r, c)) in SudokuIterator::<N_ROWS, N_COLS>::new(row, col, mode).enumerate() {
+        for (index, &(r, c)) in coordinates.iter().enumerate() {
 
-            coordinates[index] = (r, c);
-            let cellmask = self.get_possibilities(r, c);
+            for possibility in self.get_possibilities(r, c) {
 
-            for possibility in PossibilityIterator::new(cellmask) {
+                places[possibility as usize - 1] |= S::bit(index as u32);
+            }
+        }
 
-                // println!("Hidden Pair Check - Cell ({}, {}) has possibility {}", r, c, possibility);
+        // numbers with a single place are solved (or a hidden single)
+        let numbers: Vec<usize> = (0..max_number)
+            .filter(|&number| (2..=size).contains(&(places[number].count_ones() as usize)))
+            .collect();
 
-                places[possibility as usize - 1] |= 1 << index;
+        for combination in combinations(numbers.len(), size) {
+
+            let subset: Vec<usize> = combination.iter().map(|&index| numbers[index]).collect();
+            let subset_places = subset.iter().fold(S::EMPTY, |mask, &number| mask | places[number]);
+
+            if subset_places.count_ones() as usize != size {
+                continue;
             }
-        }
 
-        // println!("Hidden Pair Check - Places: {:09b}, {:09b}", places[0], places[1]);
-        // println!("Hidden Pair Check - Coordinates: {:?}", coordinates);
+            // create a mask to keep only the numbers in the subset
+            let keep_mask = subset.iter().fold(S::EMPTY, |mask, &number| mask | S::bit(number as u32));
 
-        for num_a in 0..max_number
-        {
-            // gets the mask of places for number a
-            let places_a = places[num_a];
-            if places_a.count_ones() != 2 { continue; }   // if it's not 2 places, skip
-
-            for num_b in (num_a + 1)..max_number
-            {
-                // gets the mask of places for number b
-                let places_b = places[num_b];
-                if places_a.count_ones() != 2 { continue; }   // if it's not 2 places, skip
-
-                if places_a == places_b { // if both masks share the same places, we found a hidden pair
-
-                    // println!("Found hidden pair: numbers {} and {} in positions {:09b}", num_a, num_b, places_a);
-
-                    // create a mask to keep only the two numbers in the pair
-                    let keep_mask: u32 = (1 << (num_a)) | (1 << (num_b));
-                    // println!("Hidden pair keep mask: {:09b}", keep_mask);
-                    
-                    // get the place index from places_a, which is the same as places_b
-                    for coord_index in PossibilityIterator::new(places_a) {
-                        let (row, col) = coordinates[coord_index as usize - 1];
-
-                        // eliminate all other possibilities from these two cells
-                        let before = self.possibilities[row][col];
-                        if before & !keep_mask != 0 {
-                            let after = before & keep_mask;
-                            self.possibilities[row][col] = after;
-                            println!("Eliminating hidden pair possibilities at ({}, {}): {:09b} & {:09b} -> {:09b}", row, col, before, keep_mask, after);
-                        }
+            for coord_index in PossibilityIterator::new(subset_places) {
+                let (r, c) = coordinates[coord_index as usize - 1];
+
+                // eliminate all other possibilities from these cells
+                let before = self.possibilities[r][c];
+                if !(before & !keep_mask).is_empty() {
+
+                    self.possibilities[r][c] = before & keep_mask;
+
+                    for number in PossibilityIterator::new(before & !keep_mask) {
+                        eliminated.push((r, c, number));
                     }
                 }
             }
         }
+
+        eliminated
     }
 
 }
 
 
-pub struct PossibilityIterator {
-    mask: u32
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize, S: CandidateStorage> Default for PencilNotes<N_ROWS, N_COLS, BOX_H, BOX_W, S> {
+
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-impl PossibilityIterator {
-    pub fn new(mask: u32) -> Self {
+
+// all ways of picking `k` out of the indices 0..n, in lexicographic order
+pub(crate) fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+
+    let mut result = Vec::new();
+
+    if k > n {
+        return result;
+    }
+
+    let mut combination: Vec<usize> = (0..k).collect();
+
+    loop {
+        result.push(combination.clone());
+
+        // find the rightmost index that can still be moved up
+        let Some(i) = (0..k).rev().find(|&i| combination[i] < n - k + i) else {
+            return result;
+        };
+
+        combination[i] += 1;
+        for j in (i + 1)..k {
+            combination[j] = combination[j - 1] + 1;
+        }
+    }
+}
+
+
+pub struct PossibilityIterator<S: CandidateStorage = u32> {
+    mask: S
+}
+
+impl<S: CandidateStorage> PossibilityIterator<S> {
+    pub fn new(mask: S) -> Self {
         PossibilityIterator { mask }
     }
 }
 
-impl Iterator for PossibilityIterator {
+impl<S: CandidateStorage> Iterator for PossibilityIterator<S> {
     type Item = u32;
 
     // This iterator yields the indices (1-based) of set bits in the mask.
 
     fn next(&mut self) -> Option<Self::Item> {
 
-        while self.mask > 0 {
+        if !self.mask.is_empty() {
             let trailing = self.mask.trailing_zeros();
-            self.mask &= self.mask - 1;
+            self.mask = self.mask.without_lowest();
             return Some(trailing + 1);
         }
         None
@@ -409,26 +613,28 @@ impl Iterator for PossibilityIterator {
 }
 
 
-pub struct HiddenSingleIterator<const N_ROWS: usize, const N_COLS: usize> {
-    counts: [u32; 32],
-    positions: [(usize, usize); 32],
+pub struct HiddenSingleIterator<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize = 3, const BOX_W: usize = 3> {
+    // indexed by number - 1
+    counts: Vec<u32>,
+    positions: Vec<(usize, usize)>,
     current: usize
 }
 
 
-impl<const N_ROWS: usize, const N_COLS: usize> HiddenSingleIterator<N_ROWS, N_COLS> {
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize> HiddenSingleIterator<N_ROWS, N_COLS, BOX_H, BOX_W> {
 
-    pub fn new(pencil_notes: &PencilNotes<N_ROWS, N_COLS>, row: usize, col: usize, mode: SudokuIteratorMode) -> Self {
+    pub fn new<S: CandidateStorage>(pencil_notes: &PencilNotes<N_ROWS, N_COLS, BOX_H, BOX_W, S>, row: usize, col: usize, mode: SudokuIteratorMode) -> Self {
+
+        let max_number = BOX_H * BOX_W;
 
         let mut iterator = HiddenSingleIterator {
-            counts: [0; 32],
-            positions: [(N_ROWS, N_COLS); 32],
+            counts: vec![0; max_number],
+            positions: vec![(N_ROWS, N_COLS); max_number],
             current: 0 };
 
-        for (r, c) in SudokuIterator::<N_ROWS, N_COLS>::new(row, col, mode) {
+        for (r, c) in SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(row, col, mode) {
          
-            let cell_possibility = pencil_notes.get_possibilities(r, c);
-            for possibility in PossibilityIterator::new(cell_possibility) {
+            for possibility in pencil_notes.get_possibilities(r, c) {
         
                 iterator.counts[possibility as usize - 1] += 1;
                 iterator.positions[possibility as usize - 1] = (r, c);
@@ -440,13 +646,13 @@ impl<const N_ROWS: usize, const N_COLS: usize> HiddenSingleIterator<N_ROWS, N_CO
 }
 
 
-impl<const N_ROWS: usize, const N_COLS: usize> Iterator for HiddenSingleIterator<N_ROWS, N_COLS> {
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize> Iterator for HiddenSingleIterator<N_ROWS, N_COLS, BOX_H, BOX_W> {
 
     type Item = (usize, usize, u32);
 
     fn next(&mut self) -> Option<Self::Item> {
 
-        let max_number:usize = N_ROWS/3 * N_COLS/3;
+        let max_number:usize = BOX_H * BOX_W;
 
         for number in self.current..max_number {
             if self.counts[number] == 1 {
@@ -471,10 +677,33 @@ impl RandomBit {
 
     pub fn new(rng: Box<dyn RngCore>) -> Self
     {
-        Self { rng: rng }
+        Self { rng }
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+
+        items.shuffle(&mut self.rng);
+    }
+
+    // a seed for an rng of its own, e.g. for work handed to another thread
+    pub fn next_seed(&mut self) -> u64 {
+
+        self.rng.next_u64()
     }
 
     pub fn select_random_bit(&mut self, bitfield: u32) -> Option<u32> {
+
+        self.select_random_candidate(bitfield)
+    }
+
+    // a random number out of `candidates`, None if there are none
+    pub fn select_random_number<S: CandidateStorage>(&mut self, candidates: Candidates<S>) -> Option<u32> {
+
+        self.select_random_candidate(candidates.bits()).map(|index| index + 1)
+    }
+
+    // like select_random_bit, for candidates of any storage
+    pub fn select_random_candidate<S: CandidateStorage>(&mut self, bitfield: S) -> Option<u32> {
     
         let number_of_ones = bitfield.count_ones();
         let mut mask = bitfield; 
@@ -486,11 +715,8 @@ impl RandomBit {
     
                 let target_index = self.rng.random_range(0..number_of_ones);
 
-                println!("Mask is: {:09b}", mask);
-
                 for _ in 0..target_index {
-                    mask &= mask - 1;
-                    println!("Mask is: {:09b}", mask);
+                    mask = mask.without_lowest();
                 }
     
                 Some(mask.trailing_zeros())
@@ -0,0 +1,393 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::sudoku_candidate_storage::CandidateStorage;
+use crate::sudoku_iterator::{SudokuIterator, SudokuIteratorMode, Unit};
+use crate::sudoku_pencil_notes::{PencilNotes, PossibilityIterator};
+use crate::sudoku_pencil_notes_chains::LinkKind;
+
+
+// A link between two candidates, (row, col, number) each.
+// Strong: if `from` is false, `to` is true. Weak: if `from` is true, `to` is false.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CandidateLink
+{
+    pub from: (usize, usize, u32),
+    pub to: (usize, usize, u32),
+    pub kind: LinkKind,
+}
+
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AicKind
+{
+    // strong links only inside bivalue cells, weak links only between cells on one number
+    XYChain,
+    // the ends of the chain are weakly linked, which closes it into a loop
+    NiceLoop,
+    // any other alternating inference chain
+    Aic,
+}
+
+
+// An alternating inference chain over candidates of any number, starting and ending with a strong link:
+// the first or the last candidate is true.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Aic
+{
+    pub kind: AicKind,
+    // (row, col, number)
+    pub nodes: Vec<(usize, usize, u32)>,
+    // for a nice loop this includes the weak link from the last node back to the first
+    pub links: Vec<CandidateLink>,
+    // (row, col, number)
+    pub eliminations: Vec<(usize, usize, u32)>,
+}
+
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ForcingKind
+{
+    // every candidate of a cell in turn
+    Cell,
+    // every place of a number in a unit in turn
+    Unit,
+}
+
+
+// One of the premises has to be true, and each of them leads to the same conclusion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForcingChain
+{
+    pub kind: ForcingKind,
+    // the unit of a unit forcing chain
+    pub unit: Option<Unit>,
+    // (row, col, number)
+    pub premises: Vec<(usize, usize, u32)>,
+    // for every premise, the chain from it to the conclusion
+    pub chains: Vec<Vec<CandidateLink>>,
+    // the conclusion is either a placement or a single elimination
+    pub placement: Option<(usize, usize, u32)>,
+    pub eliminations: Vec<(usize, usize, u32)>,
+}
+
+
+// (index into Inferences::nodes, candidate is true)
+type State = (usize, bool);
+
+
+// Every candidate and what follows from it being true or false.
+struct Inferences
+{
+    nodes: Vec<(usize, usize, u32)>,
+    index: HashMap<(usize, usize, u32), usize>,
+    // false -> true
+    strong: Vec<Vec<usize>>,
+    // true -> false
+    weak: Vec<Vec<usize>>,
+}
+
+
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize, S: CandidateStorage> PencilNotes<N_ROWS, N_COLS, BOX_H, BOX_W, S> {
+
+    // looks for the first chain of the given kind with at most max_length links that eliminates something
+    pub fn find_aic(&self, kind: AicKind, max_length: usize) -> Option<Aic> {
+
+        let inferences = self.inferences();
+        let xy_only = kind == AicKind::XYChain;
+
+        for start in 0..inferences.nodes.len() {
+
+            let (order, previous) = Self::implications(&inferences, (start, false), max_length, xy_only);
+
+            for &state in &order {
+
+                let path = Self::path(&previous, state);
+
+                // an odd chain of at least three links, ending in a true candidate
+                if !state.1 || path.len() < 4 {
+                    continue;
+                }
+
+                let mut nodes: Vec<usize> = path.iter().map(|state| state.0).collect();
+                nodes.sort();
+                nodes.dedup();
+                if nodes.len() != path.len() {
+                    continue;
+                }
+
+                let end = state.0;
+                let closed = inferences.weak[end].contains(&start);
+
+                let found = if closed { AicKind::NiceLoop } else { AicKind::Aic };
+                if kind != AicKind::XYChain && found != kind {
+                    continue;
+                }
+
+                let mut links = Self::links(&inferences, &path);
+                if closed {
+                    links.push(CandidateLink { from: inferences.nodes[end], to: inferences.nodes[start], kind: LinkKind::Weak });
+                }
+
+                let eliminations = if closed {
+                    self.loop_eliminations(&links)
+                } else {
+                    self.chain_eliminations(inferences.nodes[start], inferences.nodes[end])
+                };
+
+                if !eliminations.is_empty() {
+                    let nodes = path.iter().map(|state| inferences.nodes[state.0]).collect();
+                    return Some(Aic { kind, nodes, links, eliminations });
+                }
+            }
+        }
+
+        None
+    }
+
+    // finds a chain and removes its eliminations
+    pub fn handle_aic(&mut self, kind: AicKind, max_length: usize) -> Option<Aic> {
+
+        let aic = self.find_aic(kind, max_length)?;
+
+        for &(r, c, number) in &aic.eliminations {
+            self.remove_possibility(r, c, number);
+        }
+
+        Some(aic)
+    }
+
+    // looks for the first forcing chain of the given kind whose premises share a conclusion.
+    // a placement is left to the caller, as the sudoku has to be updated as well.
+    pub fn find_forcing_chain(&self, kind: ForcingKind, max_length: usize) -> Option<ForcingChain> {
+
+        let inferences = self.inferences();
+
+        let mut groups: Vec<(Option<Unit>, Vec<usize>)> = Vec::new();
+        match kind {
+            ForcingKind::Cell => {
+                for (r, c) in SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(0, 0, SudokuIteratorMode::All) {
+                    let premises = PossibilityIterator::new(self.possibilities[r][c])
+                        .map(|number| inferences.index[&(r, c, number)])
+                        .collect();
+                    groups.push((None, premises));
+                }
+            },
+            ForcingKind::Unit => {
+                for unit in Unit::all::<N_ROWS, N_COLS, BOX_H, BOX_W>() {
+                    for number in 1..=(BOX_H * BOX_W) as u32 {
+                        let premises = unit.iter::<N_ROWS, N_COLS, BOX_H, BOX_W>()
+                            .filter_map(|(r, c)| inferences.index.get(&(r, c, number)).copied())
+                            .collect();
+                        groups.push((Some(unit), premises));
+                    }
+                }
+            },
+        }
+
+        for (unit, premises) in groups {
+
+            if premises.len() < 2 {
+                continue;
+            }
+
+            let results: Vec<(Vec<State>, HashMap<State, State>)> = premises.iter()
+                .map(|&premise| Self::implications(&inferences, (premise, true), max_length, false))
+                .collect();
+
+            let reached_by_all = |state: State| results.iter().all(|(_, previous)| previous.contains_key(&state));
+
+            // true in every case comes before false in every case
+            let conclusion = [true, false].into_iter()
+                .flat_map(|on| (0..inferences.nodes.len()).map(move |node| (node, on)))
+                .find(|&(node, on)| {
+                    let (r, c, _) = inferences.nodes[node];
+                    self.possibilities[r][c].count_ones() > 1 && !premises.contains(&node) && reached_by_all((node, on))
+                });
+
+            let Some(conclusion) = conclusion else {
+                continue;
+            };
+
+            let chains = results.iter()
+                .map(|(_, previous)| Self::links(&inferences, &Self::path(previous, conclusion)))
+                .collect();
+
+            let candidate = inferences.nodes[conclusion.0];
+            let (placement, eliminations) = if conclusion.1 { (Some(candidate), Vec::new()) } else { (None, vec![candidate]) };
+
+            return Some(ForcingChain {
+                kind,
+                unit,
+                premises: premises.iter().map(|&premise| inferences.nodes[premise]).collect(),
+                chains,
+                placement,
+                eliminations,
+            });
+        }
+
+        None
+    }
+
+    fn inferences(&self) -> Inferences {
+
+        let mut nodes = Vec::new();
+        for (r, c) in SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(0, 0, SudokuIteratorMode::All) {
+            for number in PossibilityIterator::new(self.possibilities[r][c]) {
+                nodes.push((r, c, number));
+            }
+        }
+
+        let index: HashMap<(usize, usize, u32), usize> = nodes.iter().enumerate().map(|(i, &node)| (node, i)).collect();
+
+        let mut strong = vec![Vec::new(); nodes.len()];
+        let mut weak = vec![Vec::new(); nodes.len()];
+
+        for (i, &(r, c, number)) in nodes.iter().enumerate() {
+
+            let mask = self.possibilities[r][c];
+
+            // the only other candidate of a bivalue cell
+            if mask.count_ones() == 2 {
+                let other = PossibilityIterator::new(mask).find(|&other| other != number).unwrap();
+                strong[i].push(index[&(r, c, other)]);
+            }
+
+            // the only other place in a unit
+            for mode in [SudokuIteratorMode::Row, SudokuIteratorMode::Column, SudokuIteratorMode::Square] {
+
+                let places: Vec<(usize, usize)> = SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(r, c, mode)
+                    .filter(|&(pr, pc)| self.has_possibility(pr, pc, number))
+                    .collect();
+
+                if places.len() == 2 {
+                    let (pr, pc) = places.iter().copied().find(|&place| place != (r, c)).unwrap();
+                    let other = index[&(pr, pc, number)];
+                    if !strong[i].contains(&other) {
+                        strong[i].push(other);
+                    }
+                }
+            }
+
+            for other in PossibilityIterator::new(mask).filter(|&other| other != number) {
+                weak[i].push(index[&(r, c, other)]);
+            }
+
+            for &(pr, pc) in self.geometry().peers(r, c) {
+                if self.has_possibility(pr, pc, number) {
+                    weak[i].push(index[&(pr, pc, number)]);
+                }
+            }
+        }
+
+        Inferences { nodes, index, strong, weak }
+    }
+
+    // everything that follows from the start within max_length links, in the order it was found,
+    // and for every state the state it followed from
+    fn implications(inferences: &Inferences, start: State, max_length: usize, xy_only: bool) -> (Vec<State>, HashMap<State, State>) {
+
+        let mut order = Vec::new();
+        let mut previous: HashMap<State, State> = HashMap::new();
+        let mut queue = VecDeque::from([(start, 0)]);
+
+        while let Some((state, length)) = queue.pop_front() {
+
+            order.push(state);
+
+            if length == max_length {
+                continue;
+            }
+
+            let (node, on) = state;
+            let (r, c, _) = inferences.nodes[node];
+            let next = if on { &inferences.weak[node] } else { &inferences.strong[node] };
+
+            for &other in next {
+
+                let (or, oc, _) = inferences.nodes[other];
+
+                // XY-Chains switch numbers inside a cell and keep the number between cells
+                if xy_only && on == ((or, oc) == (r, c)) {
+                    continue;
+                }
+
+                let next_state = (other, !on);
+                if next_state != start && !previous.contains_key(&next_state) {
+                    previous.insert(next_state, state);
+                    queue.push_back((next_state, length + 1));
+                }
+            }
+        }
+
+        (order, previous)
+    }
+
+    fn path(previous: &HashMap<State, State>, mut state: State) -> Vec<State> {
+
+        let mut path = vec![state];
+        while let Some(&before) = previous.get(&state) {
+            path.push(before);
+            state = before;
+        }
+        path.reverse();
+        path
+    }
+
+    fn links(inferences: &Inferences, path: &[State]) -> Vec<CandidateLink> {
+
+        path.windows(2)
+            .map(|pair| CandidateLink {
+                from: inferences.nodes[pair[0].0],
+                to: inferences.nodes[pair[1].0],
+                kind: if pair[1].1 { LinkKind::Strong } else { LinkKind::Weak },
+            })
+            .collect()
+    }
+
+    // one of the two ends is true
+    fn chain_eliminations(&self, (r1, c1, n1): (usize, usize, u32), (r2, c2, n2): (usize, usize, u32)) -> Vec<(usize, usize, u32)> {
+
+        if n1 == n2 {
+            return self.seeing_all(&[(r1, c1), (r2, c2)], n1);
+        }
+
+        let mut eliminations = Vec::new();
+        if SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::sees(r1, c1, r2, c2) {
+            if self.has_possibility(r1, c1, n2) {
+                eliminations.push((r1, c1, n2));
+            }
+            if self.has_possibility(r2, c2, n1) {
+                eliminations.push((r2, c2, n1));
+            }
+        }
+        eliminations
+    }
+
+    // in a continuous loop every weak link holds exactly one true candidate
+    fn loop_eliminations(&self, links: &[CandidateLink]) -> Vec<(usize, usize, u32)> {
+
+        let mut eliminations = Vec::new();
+
+        for link in links.iter().filter(|link| link.kind == LinkKind::Weak) {
+
+            let ((r1, c1, n1), (r2, c2, n2)) = (link.from, link.to);
+
+            let found = if (r1, c1) == (r2, c2) {
+                PossibilityIterator::new(self.possibilities[r1][c1])
+                    .filter(|&number| number != n1 && number != n2)
+                    .map(|number| (r1, c1, number))
+                    .collect()
+            } else {
+                self.seeing_all(&[(r1, c1), (r2, c2)], n1)
+            };
+
+            for elimination in found {
+                if !eliminations.contains(&elimination) {
+                    eliminations.push(elimination);
+                }
+            }
+        }
+
+        eliminations
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::HashSet;
+
+use crate::sudoku_candidate_storage::CandidateStorage;
+use crate::sudoku_candidates::Candidates;
+use crate::sudoku_iterator::{SudokuIterator, Unit};
+use crate::sudoku_pencil_notes::{PencilNotes, PossibilityIterator};
+
+
+// the largest almost locked set looked for, in cells. larger sets rarely lead anywhere
+// a player would follow, and their number grows exponentially with the grid size.
+pub const MAX_ALS_SIZE: usize = 5;
+
+
+// An almost locked set: N cells of one unit holding N + 1 numbers between them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Als<S: CandidateStorage = u32>
+{
+    pub unit: Unit,
+    pub cells: Vec<(usize, usize)>,
+    // bit mask of the numbers, like PencilNotes::possibilities
+    pub candidates: S,
+}
+
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AlsKind
+{
+    // two sets linked by a restricted common number
+    AlsXZ,
+    // two sets each linked to a third one, by different restricted common numbers
+    AlsXYWing,
+}
+
+
+// Sets linked by restricted common numbers: a number whose places in two sets all see each other,
+// so it can be in at most one of them. For an ALS-XY-Wing the last set is the one in the middle.
+// In either case one of the first two sets has to hold `number`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlsChain<S: CandidateStorage = u32>
+{
+    pub kind: AlsKind,
+    pub sets: Vec<Als<S>>,
+    pub restricted: Vec<u32>,
+    // the number that is eliminated
+    pub number: u32,
+    // (row, col, number)
+    pub eliminations: Vec<(usize, usize, u32)>,
+}
+
+
+impl<S: CandidateStorage> Als<S> {
+
+    // the cells of the set holding the number
+    pub fn places<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize>(&self, pencil_notes: &PencilNotes<N_ROWS, N_COLS, BOX_H, BOX_W, S>, number: u32) -> Vec<(usize, usize)>
+    {
+        self.cells.iter().copied().filter(|&(r, c)| pencil_notes.has_possibility(r, c, number)).collect()
+    }
+
+    pub fn overlaps(&self, other: &Als<S>) -> bool
+    {
+        self.cells.iter().any(|cell| other.cells.contains(cell))
+    }
+}
+
+
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize, S: CandidateStorage> PencilNotes<N_ROWS, N_COLS, BOX_H, BOX_W, S> {
+
+    // every almost locked set of up to MAX_ALS_SIZE cells of every unit.
+    // a set sharing more than one unit is only listed once.
+    pub fn find_als(&self) -> Vec<Als<S>> {
+
+        let mut sets: Vec<Als<S>> = Vec::new();
+        let mut seen: HashSet<Vec<(usize, usize)>> = HashSet::new();
+
+        for unit in Unit::all::<N_ROWS, N_COLS, BOX_H, BOX_W>() {
+
+            let (row, col, mode) = unit.to_mode::<N_ROWS, N_COLS, BOX_H, BOX_W>();
+            let unsolved: Vec<(usize, usize)> = SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(row, col, mode)
+                .filter(|&(r, c)| self.possibilities[r][c].count_ones() > 1)
+                .collect();
+
+            // with all unsolved cells the set would be locked, not almost locked
+            for size in 1..unsolved.len().min(MAX_ALS_SIZE + 1) {
+
+                let mut found = Vec::new();
+                self.collect_als(&unsolved, size, 0, &mut Vec::new(), S::EMPTY, &mut found);
+
+                for (cells, candidates) in found {
+
+                    let mut key = cells.clone();
+                    key.sort();
+                    if seen.insert(key) {
+                        sets.push(Als { unit, cells, candidates });
+                    }
+                }
+            }
+        }
+
+        sets
+    }
+
+    // the sets of `size` cells from `unsolved` holding size + 1 numbers, in lexicographic order.
+    // a partial set with more than size + 1 numbers can't shrink again and is not extended.
+    fn collect_als(&self, unsolved: &[(usize, usize)], size: usize, start: usize, cells: &mut Vec<(usize, usize)>, candidates: S, found: &mut Vec<(Vec<(usize, usize)>, S)>) {
+
+        if cells.len() == size {
+            if candidates.count_ones() as usize == size + 1 {
+                found.push((cells.clone(), candidates));
+            }
+            return;
+        }
+
+        for index in start..unsolved.len() - (size - cells.len() - 1) {
+
+            let (r, c) = unsolved[index];
+            let union = candidates | self.possibilities[r][c];
+            if union.count_ones() as usize > size + 1 {
+                continue;
+            }
+
+            cells.push((r, c));
+            self.collect_als(unsolved, size, index + 1, cells, union, found);
+            cells.pop();
+        }
+    }
+
+    // looks for the first ALS chain of the given kind that eliminates something
+    pub fn find_als_chain(&self, kind: AlsKind) -> Option<AlsChain<S>> {
+
+        let sets = self.find_als();
+
+        // (other set, restricted common number) for every set
+        let mut links: Vec<Vec<(usize, u32)>> = vec![Vec::new(); sets.len()];
+        for a in 0..sets.len() {
+            for b in (a + 1)..sets.len() {
+                for number in self.restricted_commons(&sets[a], &sets[b]) {
+                    links[a].push((b, number));
+                    links[b].push((a, number));
+                }
+            }
+        }
+
+        match kind {
+            AlsKind::AlsXZ => {
+                for (a, a_links) in links.iter().enumerate() {
+                    for &(b, x) in a_links.iter().filter(|&&(b, _)| b > a) {
+
+                        if let Some(chain) = self.als_chain(kind, vec![&sets[a], &sets[b]], vec![x]) {
+                            return Some(chain);
+                        }
+                    }
+                }
+            },
+            AlsKind::AlsXYWing => {
+                for (c, c_links) in links.iter().enumerate() {
+                    for (index, &(a, x)) in c_links.iter().enumerate() {
+                        for &(b, y) in &c_links[index + 1..] {
+
+                            if a == b || x == y || sets[a].overlaps(&sets[b]) {
+                                continue;
+                            }
+
+                            if let Some(chain) = self.als_chain(kind, vec![&sets[a], &sets[b], &sets[c]], vec![x, y]) {
+                                return Some(chain);
+                            }
+                        }
+                    }
+                }
+            },
+        }
+
+        None
+    }
+
+    // finds an ALS chain and removes its eliminations
+    pub fn handle_als_chain(&mut self, kind: AlsKind) -> Option<AlsChain<S>> {
+
+        let chain = self.find_als_chain(kind)?;
+
+        for &(r, c, number) in &chain.eliminations {
+            self.remove_possibility(r, c, number);
+        }
+
+        Some(chain)
+    }
+
+    // numbers in both sets whose places all see each other
+    fn restricted_commons(&self, a: &Als<S>, b: &Als<S>) -> Vec<u32> {
+
+        if a.overlaps(b) {
+            return Vec::new();
+        }
+
+        PossibilityIterator::new(a.candidates & b.candidates)
+            .filter(|&number| {
+                let b_places = b.places(self, number);
+                a.places(self, number).iter().all(|&(ar, ac)| b_places.iter().all(|&(br, bc)| SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::sees(ar, ac, br, bc)))
+            })
+            .collect()
+    }
+
+    // the restricted commons can't be in both first sets, so one of them is locked without it:
+    // every other number both of them share has to be in one of them
+    fn als_chain(&self, kind: AlsKind, sets: Vec<&Als<S>>, restricted: Vec<u32>) -> Option<AlsChain<S>> {
+
+        let (a, b) = (sets[0], sets[1]);
+        let excluded = restricted.iter().copied().collect::<Candidates<S>>().bits();
+
+        for number in PossibilityIterator::new(a.candidates & b.candidates & !excluded) {
+
+            let mut places = a.places(self, number);
+            places.extend(b.places(self, number));
+
+            let eliminations: Vec<(usize, usize, u32)> = self.seeing_all(&places, number).into_iter()
+                .filter(|&(r, c, _)| !sets.iter().any(|set| set.cells.contains(&(r, c))))
+                .collect();
+
+            if !eliminations.is_empty() {
+                return Some(AlsChain {
+                    kind,
+                    sets: sets.into_iter().cloned().collect(),
+                    restricted,
+                    number,
+                    eliminations,
+                });
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,363 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::sudoku_candidate_storage::CandidateStorage;
+use crate::sudoku_iterator::{SudokuIterator, SudokuIteratorMode, Unit};
+use crate::sudoku_pencil_notes::PencilNotes;
+
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LinkKind
+{
+    // the number has only these two places in the unit: if one is false, the other is true
+    Strong,
+    // both cells see each other: if one is true, the other is false
+    Weak,
+}
+
+
+// Two places of a number that share a unit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Link
+{
+    pub from: (usize, usize),
+    pub to: (usize, usize),
+    pub kind: LinkKind,
+    pub unit: Unit,
+}
+
+impl Link {
+
+    // the end of the link that isn't `cell`
+    pub fn other(&self, cell: (usize, usize)) -> (usize, usize)
+    {
+        if self.from == cell { self.to } else { self.from }
+    }
+}
+
+
+// The places of a single number and the links between them.
+// Every pair of places sharing a unit is linked once; strong wins over weak.
+#[derive(Debug, Clone)]
+pub struct LinkGraph
+{
+    pub number: u32,
+    pub cells: Vec<(usize, usize)>,
+    pub links: Vec<Link>,
+    // indices into links for every cell
+    adjacency: HashMap<(usize, usize), Vec<usize>>,
+}
+
+impl LinkGraph {
+
+    // links touching the cell. a strong link can always be used as a weak one,
+    // so asking for weak links yields all of them.
+    pub fn links_of(&self, cell: (usize, usize), kind: LinkKind) -> impl Iterator<Item = &Link>
+    {
+        self.adjacency.get(&cell)
+            .into_iter()
+            .flatten()
+            .map(|&index| &self.links[index])
+            .filter(move |link| kind == LinkKind::Weak || link.kind == LinkKind::Strong)
+    }
+
+    pub fn link(&self, a: (usize, usize), b: (usize, usize)) -> Option<&Link>
+    {
+        self.links_of(a, LinkKind::Weak).find(|link| link.other(a) == b)
+    }
+}
+
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ChainKind
+{
+    // two colors over a cluster of strong links, one of them is true
+    SimpleColoring,
+    // two parallel strong links joined at one end by a shared row or column
+    Skyscraper,
+    // a strong link in a row and one in a column joined in a square
+    TwoStringKite,
+    // strong and weak links alternating, starting and ending with a strong link
+    XChain,
+}
+
+
+// A single number chain. For a simple coloring the links span the cluster,
+// for all others they follow the chain from one end to the other.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chain
+{
+    pub kind: ChainKind,
+    pub number: u32,
+    // every cell with its color. the two colors can't both be false.
+    pub nodes: Vec<((usize, usize), bool)>,
+    // the kind of a link is the role it plays in the chain
+    pub links: Vec<Link>,
+    // (row, col, number)
+    pub eliminations: Vec<(usize, usize, u32)>,
+}
+
+
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize, S: CandidateStorage> PencilNotes<N_ROWS, N_COLS, BOX_H, BOX_W, S> {
+
+    pub fn link_graph(&self, number: u32) -> LinkGraph {
+
+        let cells: Vec<(usize, usize)> = SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(0, 0, SudokuIteratorMode::All)
+            .filter(|&(r, c)| self.has_possibility(r, c, number))
+            .collect();
+
+        // (from, to) -> index into links
+        type Pair = ((usize, usize), (usize, usize));
+
+        let mut links: Vec<Link> = Vec::new();
+        let mut pairs: HashMap<Pair, usize> = HashMap::new();
+
+        for unit in Unit::all::<N_ROWS, N_COLS, BOX_H, BOX_W>() {
+
+            let places: Vec<(usize, usize)> = unit.iter::<N_ROWS, N_COLS, BOX_H, BOX_W>()
+                .filter(|&(r, c)| self.has_possibility(r, c, number))
+                .collect();
+
+            let kind = if places.len() == 2 { LinkKind::Strong } else { LinkKind::Weak };
+
+            for (index, &from) in places.iter().enumerate() {
+                for &to in &places[index + 1..] {
+
+                    let link = Link { from, to, kind, unit };
+
+                    match pairs.get(&(from, to)) {
+                        Some(&existing) if kind == LinkKind::Strong => links[existing] = link,
+                        Some(_) => {},
+                        None => {
+                            pairs.insert((from, to), links.len());
+                            links.push(link);
+                        },
+                    }
+                }
+            }
+        }
+
+        let mut adjacency: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (index, link) in links.iter().enumerate() {
+            adjacency.entry(link.from).or_default().push(index);
+            adjacency.entry(link.to).or_default().push(index);
+        }
+
+        LinkGraph { number, cells, links, adjacency }
+    }
+
+    // looks for the first chain of the given kind that eliminates something
+    pub fn find_chain(&self, kind: ChainKind) -> Option<Chain> {
+
+        let max_number = (BOX_H * BOX_W) as u32;
+
+        for number in 1..=max_number {
+
+            let graph = self.link_graph(number);
+
+            let chain = match kind {
+                ChainKind::SimpleColoring => self.find_coloring(&graph),
+                ChainKind::Skyscraper | ChainKind::TwoStringKite => self.find_two_links(&graph, kind),
+                ChainKind::XChain => self.find_x_chain(&graph),
+            };
+
+            if chain.is_some() {
+                return chain;
+            }
+        }
+
+        None
+    }
+
+    // finds a chain and removes its eliminations
+    pub fn handle_chain(&mut self, kind: ChainKind) -> Option<Chain> {
+
+        let chain = self.find_chain(kind)?;
+
+        for &(r, c, number) in &chain.eliminations {
+            self.remove_possibility(r, c, number);
+        }
+
+        Some(chain)
+    }
+
+    fn find_coloring(&self, graph: &LinkGraph) -> Option<Chain> {
+
+        let sees = |(r1, c1): (usize, usize), (r2, c2): (usize, usize)| SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::sees(r1, c1, r2, c2);
+        let mut colored: HashSet<(usize, usize)> = HashSet::new();
+
+        for &start in &graph.cells {
+
+            if colored.contains(&start) {
+                continue;
+            }
+
+            // color the cluster of strong links around start
+            let mut nodes = vec![(start, false)];
+            let mut links = Vec::new();
+            let mut queue = VecDeque::from([(start, false)]);
+            colored.insert(start);
+
+            while let Some((cell, color)) = queue.pop_front() {
+                for link in graph.links_of(cell, LinkKind::Strong) {
+
+                    let next = link.other(cell);
+                    if colored.insert(next) {
+                        nodes.push((next, !color));
+                        links.push(*link);
+                        queue.push_back((next, !color));
+                    }
+                }
+            }
+
+            if links.is_empty() {
+                continue;
+            }
+
+            // color wrap: two cells of the same color see each other, so that color is false
+            let mut eliminations = Vec::new();
+            for color in [false, true] {
+
+                let cells: Vec<(usize, usize)> = nodes.iter().filter(|node| node.1 == color).map(|node| node.0).collect();
+                let wrapped = cells.iter().enumerate().any(|(index, &a)| cells[index + 1..].iter().any(|&b| sees(a, b)));
+
+                if wrapped {
+                    eliminations = cells.iter().map(|&(r, c)| (r, c, graph.number)).collect();
+                    break;
+                }
+            }
+
+            // color trap: a cell outside the cluster seeing both colors
+            if eliminations.is_empty() {
+                for &cell in graph.cells.iter().filter(|cell| !nodes.iter().any(|node| node.0 == **cell)) {
+
+                    let sees_color = |color: bool| nodes.iter().any(|&(node, node_color)| node_color == color && sees(cell, node));
+                    if sees_color(false) && sees_color(true) {
+                        eliminations.push((cell.0, cell.1, graph.number));
+                    }
+                }
+            }
+
+            if !eliminations.is_empty() {
+                return Some(Chain { kind: ChainKind::SimpleColoring, number: graph.number, nodes, links, eliminations });
+            }
+        }
+
+        None
+    }
+
+    // skyscrapers and 2-string kites: two strong links whose inner ends see each other
+    fn find_two_links(&self, graph: &LinkGraph, kind: ChainKind) -> Option<Chain> {
+
+        let square = |(r, c): (usize, usize)| Unit::from_mode::<N_ROWS, N_COLS, BOX_H, BOX_W>(r, c, SudokuIteratorMode::Square);
+
+        let strong: Vec<&Link> = graph.links.iter()
+            .filter(|link| link.kind == LinkKind::Strong && !matches!(link.unit, Unit::Square(_)))
+            .collect();
+
+        for (index, first) in strong.iter().enumerate() {
+            for second in &strong[index + 1..] {
+
+                let parallel = std::mem::discriminant(&first.unit) == std::mem::discriminant(&second.unit);
+                if parallel != (kind == ChainKind::Skyscraper) {
+                    continue;
+                }
+
+                for (a_end, a_inner) in [(first.from, first.to), (first.to, first.from)] {
+                    for (b_inner, b_end) in [(second.from, second.to), (second.to, second.from)] {
+
+                        let cells = [a_end, a_inner, b_inner, b_end];
+                        if cells.iter().enumerate().any(|(i, cell)| cells[i + 1..].contains(cell)) {
+                            continue;
+                        }
+
+                        let joined = match kind {
+                            // the inner ends share a column (or row), the outer ends must not
+                            ChainKind::Skyscraper => match first.unit {
+                                Unit::Row(_) => a_inner.1 == b_inner.1 && a_end.1 != b_end.1,
+                                _ => a_inner.0 == b_inner.0 && a_end.0 != b_end.0,
+                            },
+                            _ => square(a_inner) == square(b_inner),
+                        };
+                        if !joined {
+                            continue;
+                        }
+
+                        let eliminations = self.seeing_all(&[a_end, b_end], graph.number);
+                        if !eliminations.is_empty() {
+                            return Some(Self::linear_chain(graph, kind, &cells, eliminations));
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    // shortest alternating chains from every start, the first one that eliminates something wins
+    fn find_x_chain(&self, graph: &LinkGraph) -> Option<Chain> {
+
+        // (cell, arrived over a strong link)
+        type State = ((usize, usize), bool);
+
+        for &start in &graph.cells {
+
+            let mut previous: HashMap<State, State> = HashMap::new();
+            let mut queue = VecDeque::from([(start, false)]);
+
+            let path = |previous: &HashMap<State, State>, mut state: State| {
+                let mut cells = vec![state.0];
+                while let Some(&before) = previous.get(&state) {
+                    cells.push(before.0);
+                    state = before;
+                }
+                cells.reverse();
+                cells
+            };
+
+            while let Some(state) = queue.pop_front() {
+
+                let (cell, strong) = state;
+                let cells = path(&previous, state);
+
+                // an odd chain of at least three links: start or cell is true
+                if strong && cells.len() >= 4 {
+                    let eliminations = self.seeing_all(&[start, cell], graph.number);
+                    if !eliminations.is_empty() {
+                        return Some(Self::linear_chain(graph, ChainKind::XChain, &cells, eliminations));
+                    }
+                }
+
+                let next_kind = if strong { LinkKind::Weak } else { LinkKind::Strong };
+
+                for link in graph.links_of(cell, next_kind) {
+
+                    let next = (link.other(cell), !strong);
+                    if next.0 != start && !cells.contains(&next.0) && !previous.contains_key(&next) {
+                        previous.insert(next, state);
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    // the chain through the cells, with strong and weak links alternating
+    fn linear_chain(graph: &LinkGraph, kind: ChainKind, cells: &[(usize, usize)], eliminations: Vec<(usize, usize, u32)>) -> Chain {
+
+        let links = cells.windows(2)
+            .enumerate()
+            .map(|(index, pair)| {
+                let link = graph.link(pair[0], pair[1]).unwrap();
+                let kind = if index % 2 == 0 { LinkKind::Strong } else { LinkKind::Weak };
+                Link { from: pair[0], to: pair[1], kind, unit: link.unit }
+            })
+            .collect();
+
+        let nodes = cells.iter().enumerate().map(|(index, &cell)| (cell, index % 2 == 1)).collect();
+
+        Chain { kind, number: graph.number, nodes, links, eliminations }
+    }
+}
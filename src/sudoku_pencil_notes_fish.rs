@@ -0,0 +1,169 @@
+use crate::sudoku_candidate_storage::CandidateStorage;
+use crate::sudoku_candidates::Candidates;
+use crate::sudoku_iterator::{SudokuIteratorMode, Unit};
+use crate::sudoku_pencil_notes::{combinations, PencilNotes, PossibilityIterator};
+
+
+// A basic or finned fish on a single number.
+// The number's places in the base lines all lie in the cover lines (apart from the fins),
+// so it can't go anywhere else in the cover lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fish
+{
+    pub number: u32,
+    // 2 = X-Wing, 3 = Swordfish, 4 = Jellyfish
+    pub size: usize,
+    pub base: Vec<Unit>,
+    pub cover: Vec<Unit>,
+    // places of the number in the base lines outside of the cover lines, all in one square
+    pub fins: Vec<(usize, usize)>,
+    // a finned fish where a base line has a single place left in the cover lines
+    pub sashimi: bool,
+    // (row, col, number)
+    pub eliminations: Vec<(usize, usize, u32)>,
+}
+
+
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize, S: CandidateStorage> PencilNotes<N_ROWS, N_COLS, BOX_H, BOX_W, S> {
+
+    // looks for the first fish of the given size that eliminates something.
+    // with `finned` set only finned (and sashimi) fish are searched.
+    pub fn find_fish(&self, size: usize, finned: bool) -> Option<Fish> {
+
+        let max_number = (BOX_H * BOX_W) as u32;
+
+        for number in 1..=max_number {
+            for rows_as_base in [true, false] {
+
+                if let Some(fish) = self.find_fish_for(number, size, finned, rows_as_base) {
+                    return Some(fish);
+                }
+            }
+        }
+
+        None
+    }
+
+    // finds a fish and removes its eliminations
+    pub fn handle_fish(&mut self, size: usize, finned: bool) -> Option<Fish> {
+
+        let fish = self.find_fish(size, finned)?;
+
+        for &(r, c, number) in &fish.eliminations {
+            self.remove_possibility(r, c, number);
+        }
+
+        Some(fish)
+    }
+
+    fn find_fish_for(&self, number: u32, size: usize, finned: bool, rows_as_base: bool) -> Option<Fish> {
+
+        // "lines" are the base candidates, "crosses" the cover candidates
+        let (n_lines, n_crosses) = if rows_as_base { (N_ROWS, N_COLS) } else { (N_COLS, N_ROWS) };
+        let cell = |line: usize, cross: usize| if rows_as_base { (line, cross) } else { (cross, line) };
+        let line_unit = |line: usize| if rows_as_base { Unit::Row(line) } else { Unit::Column(line) };
+        let cross_unit = |cross: usize| if rows_as_base { Unit::Column(cross) } else { Unit::Row(cross) };
+
+        let mask = Candidates::<S>::from_numbers(&[number]).bits();
+
+        // the places of the number in every line, as a bit mask over the crosses
+        let mut places = vec![S::EMPTY; n_lines];
+        let mut solved = vec![false; n_lines];
+
+        for line in 0..n_lines {
+            for cross in 0..n_crosses {
+
+                let (r, c) = cell(line, cross);
+                if self.has_possibility(r, c, number) {
+                    places[line] |= S::bit(cross as u32);
+                }
+                if self.possibilities[r][c] == mask {
+                    solved[line] = true;
+                }
+            }
+        }
+
+        let lines: Vec<usize> = (0..n_lines)
+            .filter(|&line| !solved[line] && places[line].count_ones() >= 2)
+            .filter(|&line| finned || places[line].count_ones() as usize <= size)
+            .collect();
+
+        for combination in combinations(lines.len(), size) {
+
+            let base: Vec<usize> = combination.iter().map(|&index| lines[index]).collect();
+            let union = base.iter().fold(S::EMPTY, |union, &line| union | places[line]);
+
+            // (row, col, number) in the cover crosses outside the base lines, optionally restricted to a square
+            let eliminations = |cover: S, square: Option<Unit>| -> Vec<(usize, usize, u32)> {
+                let mut eliminations = Vec::new();
+                for cross in PossibilityIterator::new(cover) {
+                    for line in (0..n_lines).filter(|line| !base.contains(line)) {
+
+                        let (r, c) = cell(line, cross as usize - 1);
+                        let in_square = square.is_none_or(|square| square.contains::<N_ROWS, N_COLS, BOX_H, BOX_W>(r, c));
+
+                        if in_square && self.has_possibility(r, c, number) {
+                            eliminations.push((r, c, number));
+                        }
+                    }
+                }
+                eliminations
+            };
+
+            let fish = |cover: S, fins: Vec<(usize, usize)>, eliminations: Vec<(usize, usize, u32)>| Fish {
+                number,
+                size,
+                base: base.iter().map(|&line| line_unit(line)).collect(),
+                cover: PossibilityIterator::new(cover).map(|cross| cross_unit(cross as usize - 1)).collect(),
+                sashimi: !fins.is_empty() && base.iter().any(|&line| (places[line] & cover).count_ones() < 2),
+                fins,
+                eliminations,
+            };
+
+            if !finned {
+
+                if union.count_ones() as usize != size {
+                    continue;
+                }
+
+                let eliminations = eliminations(union, None);
+                if !eliminations.is_empty() {
+                    return Some(fish(union, Vec::new(), eliminations));
+                }
+
+                continue;
+            }
+
+            // without a place outside of the cover it would be a basic fish
+            if union.count_ones() as usize <= size {
+                continue;
+            }
+
+            let crosses: Vec<u32> = PossibilityIterator::new(union).collect();
+
+            for cover_combination in combinations(crosses.len(), size) {
+
+                let cover = cover_combination.iter().map(|&index| crosses[index]).collect::<Candidates<S>>().bits();
+
+                let fins: Vec<(usize, usize)> = base.iter()
+                    .flat_map(|&line| PossibilityIterator::new(places[line] & !cover).map(move |cross| cell(line, cross as usize - 1)))
+                    .collect();
+
+                let (fin_r, fin_c) = fins[0];
+                let fin_square = Unit::from_mode::<N_ROWS, N_COLS, BOX_H, BOX_W>(fin_r, fin_c, SudokuIteratorMode::Square).unwrap();
+
+                if !fins.iter().all(|&(r, c)| fin_square.contains::<N_ROWS, N_COLS, BOX_H, BOX_W>(r, c)) {
+                    continue;
+                }
+
+                // either a fin is true, or the fish is: only cells seeing all fins are safe to eliminate
+                let eliminations = eliminations(cover, Some(fin_square));
+                if !eliminations.is_empty() {
+                    return Some(fish(cover, fins, eliminations));
+                }
+            }
+        }
+
+        None
+    }
+}
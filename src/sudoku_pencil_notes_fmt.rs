@@ -1,15 +1,17 @@
 use std::cmp;
 use std::fmt;
 
+use crate::sudoku_candidate_storage::CandidateStorage;
+use crate::sudoku_candidates::Candidates;
 use crate::sudoku_pencil_notes::PencilNotes;
 
-impl<const N_ROWS: usize, const N_COLS: usize> fmt::Display for PencilNotes<N_ROWS, N_COLS> {
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize, S: CandidateStorage> fmt::Display for PencilNotes<N_ROWS, N_COLS, BOX_H, BOX_W, S> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         
         // 1. DIMENSION CALCULATIONS
-        // Same logic as your template: Blocks are derived by dividing by 3.
-        let block_h = (N_ROWS / 3).max(1); // e.g., 3 for 9x9
-        let block_w = (N_COLS / 3).max(1); // e.g., 3 for 9x9
+        // Same logic as your template: Blocks are the boxes of the grid.
+        let block_h = BOX_H; // e.g., 3 for 9x9
+        let block_w = BOX_W; // e.g., 3 for 9x9
         
         // Calculate max number width (e.g. "9" is 1 char, "16" is 2 chars)
         let max_dim = cmp::max(N_ROWS, N_COLS);
@@ -66,7 +68,7 @@ impl<const N_ROWS: usize, const N_COLS: usize> fmt::Display for PencilNotes<N_RO
                         let number = (sub_r * block_w) + sub_c + 1;
                         
                         // Check if this number is available in the bitmask
-                        let is_possible = (mask & (1 << (number - 1))) != 0;
+                        let is_possible = Candidates::from_bits(*mask).contains(number as u32);
 
                         if is_possible {
                             // Print number padded (e.g., " 5" or "12")
@@ -104,9 +106,9 @@ impl<const N_ROWS: usize, const N_COLS: usize> fmt::Display for PencilNotes<N_RO
                 // If we are between rows but not at a block boundary, 
                 // we print a thinner separator line for readability
                 write!(f, "+")?;
-                for i in 0..N_COLS {
+                for _ in 0..N_COLS {
                     for _ in 0..cell_text_width { write!(f, "-")?; }
-                    if (i + 1) % block_w == 0 { write!(f, "+")?; } else { write!(f, "+")?; }
+                    write!(f, "+")?;
                 }
                 writeln!(f)?;
             }
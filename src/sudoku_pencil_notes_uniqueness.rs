@@ -0,0 +1,255 @@
+use crate::sudoku_candidate_storage::CandidateStorage;
+use crate::sudoku_candidates::Candidates;
+use crate::sudoku_iterator::{SudokuIterator, SudokuIteratorMode, Unit};
+use crate::sudoku_pencil_notes::{combinations, PencilNotes, PossibilityIterator};
+
+
+// Techniques that are only sound if the puzzle has a single solution.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UniquenessKind
+{
+    // three corners hold only {a, b}: the fourth can't be a or b
+    UniqueRectangle1,
+    // the roof holds the same single extra number c: c is in one of them
+    UniqueRectangle2,
+    // the extra numbers of the roof form a naked subset with other cells of a unit
+    UniqueRectangle3,
+    // a or b only has the roof left in a unit: the other one can't be in the roof
+    UniqueRectangle4,
+    // all cells but one are bivalue: that one has to take the number that shows up three times
+    BugPlusOne,
+}
+
+
+// A deadly pattern that has to be avoided. For unique rectangles the cells are the floor
+// (the corners holding only {a, b}) followed by the roof, for BUG+1 it is the single trivalue cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Uniqueness
+{
+    pub kind: UniquenessKind,
+    pub cells: Vec<(usize, usize)>,
+    // {a, b} of a unique rectangle, the placed number of BUG+1
+    pub numbers: Vec<u32>,
+    pub placement: Option<(usize, usize, u32)>,
+    // (row, col, number)
+    pub eliminations: Vec<(usize, usize, u32)>,
+}
+
+
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize, S: CandidateStorage> PencilNotes<N_ROWS, N_COLS, BOX_H, BOX_W, S> {
+
+    // looks for the first deadly pattern of the given kind that leads somewhere.
+    // a placement is left to the caller, as the sudoku has to be updated as well.
+    pub fn find_uniqueness(&self, kind: UniquenessKind) -> Option<Uniqueness> {
+
+        match kind {
+            UniquenessKind::BugPlusOne => self.find_bug_plus_one(),
+            _ => self.find_unique_rectangle(kind),
+        }
+    }
+
+    // finds a unique rectangle and removes its eliminations
+    pub fn handle_uniqueness(&mut self, kind: UniquenessKind) -> Option<Uniqueness> {
+
+        let uniqueness = self.find_uniqueness(kind)?;
+
+        for &(r, c, number) in &uniqueness.eliminations {
+            self.remove_possibility(r, c, number);
+        }
+
+        Some(uniqueness)
+    }
+
+    fn find_unique_rectangle(&self, kind: UniquenessKind) -> Option<Uniqueness> {
+
+        for r1 in 0..N_ROWS {
+            for r2 in (r1 + 1)..N_ROWS {
+                for c1 in 0..N_COLS {
+                    for c2 in (c1 + 1)..N_COLS {
+
+                        // the rectangle has to span exactly two squares
+                        if (r1 / BOX_H == r2 / BOX_H) == (c1 / BOX_W == c2 / BOX_W) {
+                            continue;
+                        }
+
+                        let corners = [(r1, c1), (r1, c2), (r2, c1), (r2, c2)];
+                        let common = corners.iter().fold(!S::EMPTY, |common, &(r, c)| common & self.possibilities[r][c]);
+
+                        let numbers: Vec<u32> = PossibilityIterator::new(common).collect();
+
+                        for pair in combinations(numbers.len(), 2) {
+
+                            let (a, b) = (numbers[pair[0]], numbers[pair[1]]);
+                            if let Some(uniqueness) = self.check_rectangle(kind, corners, a, b) {
+                                return Some(uniqueness);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    fn check_rectangle(&self, kind: UniquenessKind, corners: [(usize, usize); 4], a: u32, b: u32) -> Option<Uniqueness> {
+
+        let ab = Candidates::<S>::from_numbers(&[a, b]).bits();
+        let mask = |(r, c): (usize, usize)| self.possibilities[r][c];
+
+        let uniqueness = |cells: Vec<(usize, usize)>, eliminations: Vec<(usize, usize, u32)>| Uniqueness {
+            kind,
+            cells,
+            numbers: vec![a, b],
+            placement: None,
+            eliminations,
+        };
+
+        if kind == UniquenessKind::UniqueRectangle1 {
+
+            let floor: Vec<(usize, usize)> = corners.iter().copied().filter(|&cell| mask(cell) == ab).collect();
+            if floor.len() != 3 {
+                return None;
+            }
+
+            let (r, c) = *corners.iter().find(|cell| !floor.contains(cell)).unwrap();
+            let eliminations = vec![(r, c, a), (r, c, b)];
+
+            let mut cells = floor;
+            cells.push((r, c));
+            return Some(uniqueness(cells, eliminations));
+        }
+
+        // the floor is a side of the rectangle, the roof the opposite side
+        for (floor, roof) in [([0, 1], [2, 3]), ([2, 3], [0, 1]), ([0, 2], [1, 3]), ([1, 3], [0, 2])] {
+
+            let floor = floor.map(|index| corners[index]);
+            let roof = roof.map(|index| corners[index]);
+
+            if floor.iter().any(|&cell| mask(cell) != ab) || roof.iter().any(|&cell| mask(cell) == ab) {
+                continue;
+            }
+
+            let extra = (mask(roof[0]) | mask(roof[1])) & !ab;
+
+            let shared: Vec<Unit> = Unit::all::<N_ROWS, N_COLS, BOX_H, BOX_W>().into_iter()
+                .filter(|unit| roof.iter().all(|&(r, c)| unit.contains::<N_ROWS, N_COLS, BOX_H, BOX_W>(r, c)))
+                .collect();
+
+            let eliminations = match kind {
+                UniquenessKind::UniqueRectangle2 => {
+                    if extra.count_ones() != 1 || mask(roof[0]) != mask(roof[1]) {
+                        continue;
+                    }
+                    self.seeing_all(&roof, extra.trailing_zeros() + 1)
+                },
+                UniquenessKind::UniqueRectangle3 => self.roof_subset(&roof, extra, &shared),
+                _ => {
+                    let mut eliminations = Vec::new();
+                    for (only, other) in [(a, b), (b, a)] {
+                        let conjugate = shared.iter().any(|unit| unit.iter::<N_ROWS, N_COLS, BOX_H, BOX_W>()
+                            .filter(|&(r, c)| self.has_possibility(r, c, only))
+                            .all(|cell| roof.contains(&cell)));
+
+                        if conjugate {
+                            eliminations = roof.iter().map(|&(r, c)| (r, c, other)).collect();
+                            break;
+                        }
+                    }
+                    eliminations
+                },
+            };
+
+            if !eliminations.is_empty() {
+                return Some(uniqueness(floor.iter().chain(roof.iter()).copied().collect(), eliminations));
+            }
+        }
+
+        None
+    }
+
+    // the roof acts as a single cell holding the extra numbers. together with other cells of a unit
+    // it can form a naked subset, whose numbers are removed from the rest of the unit.
+    fn roof_subset(&self, roof: &[(usize, usize); 2], extra: S, shared: &[Unit]) -> Vec<(usize, usize, u32)> {
+
+        for unit in shared {
+
+            let others: Vec<(usize, usize)> = unit.iter::<N_ROWS, N_COLS, BOX_H, BOX_W>()
+                .filter(|cell| !roof.contains(cell))
+                .filter(|&(r, c)| self.possibilities[r][c].count_ones() > 1)
+                .collect();
+
+            for size in 1..=3 {
+                for combination in combinations(others.len(), size) {
+
+                    let union = combination.iter().fold(extra, |union, &index| union | self.possibilities[others[index].0][others[index].1]);
+                    if union.count_ones() as usize != size + 1 {
+                        continue;
+                    }
+
+                    let mut eliminations = Vec::new();
+                    for (index, &(r, c)) in others.iter().enumerate() {
+                        if combination.contains(&index) {
+                            continue;
+                        }
+                        for number in PossibilityIterator::new(self.possibilities[r][c] & union) {
+                            eliminations.push((r, c, number));
+                        }
+                    }
+
+                    if !eliminations.is_empty() {
+                        return eliminations;
+                    }
+                }
+            }
+        }
+
+        Vec::new()
+    }
+
+    fn find_bug_plus_one(&self) -> Option<Uniqueness> {
+
+        let unsolved: Vec<(usize, usize)> = SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(0, 0, SudokuIteratorMode::All)
+            .filter(|&(r, c)| self.possibilities[r][c].count_ones() > 1)
+            .collect();
+
+        let others: Vec<&(usize, usize)> = unsolved.iter().filter(|&&(r, c)| self.possibilities[r][c].count_ones() != 2).collect();
+        let [&(row, col)] = others[..] else {
+            return None;
+        };
+
+        if self.possibilities[row][col].count_ones() != 3 {
+            return None;
+        }
+
+        let max_number = (BOX_H * BOX_W) as u32;
+
+        for number in PossibilityIterator::new(self.possibilities[row][col]) {
+
+            // without the number the board would be a BUG: every number twice (or not at all) in every unit
+            let mut bug = self.clone();
+            bug.remove_possibility(row, col, number);
+
+            let deadly = Unit::all::<N_ROWS, N_COLS, BOX_H, BOX_W>().iter().all(|unit| {
+                (1..=max_number).all(|n| {
+                    let count = unit.iter::<N_ROWS, N_COLS, BOX_H, BOX_W>()
+                        .filter(|&(r, c)| unsolved.contains(&(r, c)) && bug.has_possibility(r, c, n))
+                        .count();
+                    count == 0 || count == 2
+                })
+            });
+
+            if deadly {
+                return Some(Uniqueness {
+                    kind: UniquenessKind::BugPlusOne,
+                    cells: vec![(row, col)],
+                    numbers: vec![number],
+                    placement: Some((row, col, number)),
+                    eliminations: Vec::new(),
+                });
+            }
+        }
+
+        None
+    }
+}
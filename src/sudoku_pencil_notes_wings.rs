@@ -0,0 +1,191 @@
+use crate::sudoku_candidate_storage::CandidateStorage;
+use crate::sudoku_candidates::Candidates;
+use crate::sudoku_iterator::{SudokuIterator, SudokuIteratorMode, Unit};
+use crate::sudoku_pencil_notes::{PencilNotes, PossibilityIterator};
+
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum WingKind
+{
+    // bivalue pivot {x, y} seeing the pincers {x, z} and {y, z}
+    XYWing,
+    // pivot {x, y, z} seeing the pincers {x, z} and {y, z}
+    XYZWing,
+    // two cells {x, y} whose x's are connected by a strong link on x
+    WWing,
+}
+
+
+// A wing: whichever way the pincers resolve, one of them holds `number`,
+// so it can be removed from every cell seeing both (and the pivot for an XYZ-Wing).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wing
+{
+    pub kind: WingKind,
+    // None for a W-Wing
+    pub pivot: Option<(usize, usize)>,
+    pub pincers: [(usize, usize); 2],
+    // the two places of the linking number in a unit, W-Wing only
+    pub strong_link: Option<[(usize, usize); 2]>,
+    // the number that is eliminated
+    pub number: u32,
+    // (row, col, number)
+    pub eliminations: Vec<(usize, usize, u32)>,
+}
+
+
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize, S: CandidateStorage> PencilNotes<N_ROWS, N_COLS, BOX_H, BOX_W, S> {
+
+    // looks for the first wing of the given kind that eliminates something
+    pub fn find_wing(&self, kind: WingKind) -> Option<Wing> {
+
+        match kind {
+            WingKind::XYWing => self.find_pivot_wing(kind, 2),
+            WingKind::XYZWing => self.find_pivot_wing(kind, 3),
+            WingKind::WWing => self.find_w_wing(),
+        }
+    }
+
+    // finds a wing and removes its eliminations
+    pub fn handle_wing(&mut self, kind: WingKind) -> Option<Wing> {
+
+        let wing = self.find_wing(kind)?;
+
+        for &(r, c, number) in &wing.eliminations {
+            self.remove_possibility(r, c, number);
+        }
+
+        Some(wing)
+    }
+
+    fn cells_with_count(&self, count: u32) -> Vec<(usize, usize)> {
+
+        SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(0, 0, SudokuIteratorMode::All)
+            .filter(|&(r, c)| self.possibilities[r][c].count_ones() == count)
+            .collect()
+    }
+
+    // XY-Wing with a pivot of two possibilities, XYZ-Wing with a pivot of three
+    fn find_pivot_wing(&self, kind: WingKind, pivot_count: u32) -> Option<Wing> {
+
+        let bivalues = self.cells_with_count(2);
+
+        for (pivot_r, pivot_c) in self.cells_with_count(pivot_count) {
+
+            let pivot = self.possibilities[pivot_r][pivot_c];
+
+            let pincers: Vec<(usize, usize)> = bivalues.iter()
+                .copied()
+                .filter(|&(r, c)| SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::sees(pivot_r, pivot_c, r, c))
+                .collect();
+
+            for (index, &(a_r, a_c)) in pincers.iter().enumerate() {
+                for &(b_r, b_c) in &pincers[index + 1..] {
+
+                    let a = self.possibilities[a_r][a_c];
+                    let b = self.possibilities[b_r][b_c];
+
+                    // the pincers share exactly the eliminated number z
+                    let z = a & b;
+                    if a == b || z.count_ones() != 1 {
+                        continue;
+                    }
+
+                    let fits = match kind {
+                        WingKind::XYWing => (z & pivot).is_empty() && (a | b) & !z == pivot,
+                        _ => (a | b) == pivot,
+                    };
+                    if !fits {
+                        continue;
+                    }
+
+                    let number = z.trailing_zeros() + 1;
+                    let mut cells = vec![(a_r, a_c), (b_r, b_c)];
+                    if kind == WingKind::XYZWing {
+                        cells.push((pivot_r, pivot_c));
+                    }
+
+                    let eliminations = self.seeing_all(&cells, number);
+                    if !eliminations.is_empty() {
+                        return Some(Wing {
+                            kind,
+                            pivot: Some((pivot_r, pivot_c)),
+                            pincers: [(a_r, a_c), (b_r, b_c)],
+                            strong_link: None,
+                            number,
+                            eliminations,
+                        });
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    fn find_w_wing(&self) -> Option<Wing> {
+
+        let bivalues = self.cells_with_count(2);
+
+        for (index, &(a_r, a_c)) in bivalues.iter().enumerate() {
+            for &(b_r, b_c) in &bivalues[index + 1..] {
+
+                let mask = self.possibilities[a_r][a_c];
+                if mask != self.possibilities[b_r][b_c] || SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::sees(a_r, a_c, b_r, b_c) {
+                    continue;
+                }
+
+                for link in PossibilityIterator::new(mask) {
+
+                    let number = Candidates::from_bits(mask).iter().find(|&number| number != link).unwrap();
+
+                    let eliminations = self.seeing_all(&[(a_r, a_c), (b_r, b_c)], number);
+                    if eliminations.is_empty() {
+                        continue;
+                    }
+
+                    if let Some(strong_link) = self.find_strong_link(link, (a_r, a_c), (b_r, b_c)) {
+                        return Some(Wing {
+                            kind: WingKind::WWing,
+                            pivot: None,
+                            pincers: [(a_r, a_c), (b_r, b_c)],
+                            strong_link: Some(strong_link),
+                            number,
+                            eliminations,
+                        });
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    // a unit with only two places for the number, one seen by a and the other by b
+    fn find_strong_link(&self, number: u32, a: (usize, usize), b: (usize, usize)) -> Option<[(usize, usize); 2]> {
+
+        let sees = |(r1, c1): (usize, usize), (r2, c2): (usize, usize)| SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::sees(r1, c1, r2, c2);
+
+        for unit in Unit::all::<N_ROWS, N_COLS, BOX_H, BOX_W>() {
+
+            let places: Vec<(usize, usize)> = unit.iter::<N_ROWS, N_COLS, BOX_H, BOX_W>()
+                .filter(|&(r, c)| self.has_possibility(r, c, number))
+                .collect();
+
+            if places.len() != 2 || places.contains(&a) || places.contains(&b) {
+                continue;
+            }
+
+            let (first, second) = (places[0], places[1]);
+
+            if sees(a, first) && sees(b, second) {
+                return Some([first, second]);
+            }
+            if sees(a, second) && sees(b, first) {
+                return Some([second, first]);
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,129 @@
+use crate::sudoku::Sudoku;
+use crate::sudoku_candidate_storage::{DefaultStorage, Symbols};
+use crate::sudoku_logical_solver::{LogicalOutcome, LogicalSolver, Technique};
+
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty
+{
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+    Extreme,
+}
+
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Rating
+{
+    // sum of the scores of all steps taken, plus a penalty if the solver got stuck
+    pub score: u32,
+    pub difficulty: Difficulty,
+    // the hardest technique the logical solver needed
+    pub hardest: Option<Technique>,
+    // false if the puzzle can't be solved by logic alone (or not at all)
+    pub solved: bool,
+}
+
+
+// added to the score of a puzzle that can only be finished by guessing
+const STUCK_PENALTY: u32 = 10000;
+
+// upper score limits for Easy, Medium, Hard and Expert, loosely following HoDoKu
+const SCORE_LIMITS: [(u32, Difficulty); 4] = [
+    (800, Difficulty::Easy),
+    (1000, Difficulty::Medium),
+    (1600, Difficulty::Hard),
+    (1800, Difficulty::Expert),
+];
+
+
+// score of a single application of a technique, in the spirit of HoDoKu
+pub fn technique_score(technique: Technique) -> u32
+{
+    match technique {
+        Technique::NakedSingle => 4,
+        Technique::HiddenSingle => 14,
+        Technique::Pointing | Technique::Claiming => 50,
+        Technique::NakedPair => 60,
+        Technique::XWing => 140,
+        Technique::HiddenPair => 70,
+        Technique::NakedTriple => 80,
+        Technique::Swordfish => 150,
+        Technique::HiddenTriple => 100,
+        Technique::UniqueRectangle1 | Technique::UniqueRectangle2 => 100,
+        Technique::UniqueRectangle3 | Technique::UniqueRectangle4 => 100,
+        Technique::BugPlusOne => 100,
+        Technique::Skyscraper => 130,
+        Technique::TwoStringKite => 150,
+        Technique::XYWing => 160,
+        Technique::XYZWing => 180,
+        Technique::WWing => 150,
+        Technique::SimpleColoring => 150,
+        Technique::FinnedXWing => 150,
+        Technique::FinnedSwordfish => 200,
+        Technique::NakedQuad => 120,
+        Technique::Jellyfish => 160,
+        Technique::HiddenQuad => 150,
+        Technique::FinnedJellyfish => 240,
+        Technique::XChain => 260,
+        Technique::XYChain => 260,
+        Technique::NiceLoop | Technique::Aic => 280,
+        Technique::AlsXZ => 300,
+        Technique::AlsXYWing => 320,
+        Technique::CellForcingChain | Technique::UnitForcingChain => 500,
+    }
+}
+
+
+// the least difficulty a puzzle needing this technique can have
+pub fn technique_difficulty(technique: Technique) -> Difficulty
+{
+    match technique {
+        Technique::NakedSingle | Technique::HiddenSingle => Difficulty::Easy,
+        Technique::Pointing | Technique::Claiming => Difficulty::Medium,
+        Technique::NakedPair | Technique::HiddenPair => Difficulty::Medium,
+        Technique::XWing | Technique::NakedTriple | Technique::Swordfish | Technique::HiddenTriple => Difficulty::Hard,
+        Technique::UniqueRectangle1 | Technique::UniqueRectangle2 | Technique::UniqueRectangle3 => Difficulty::Hard,
+        Technique::UniqueRectangle4 | Technique::BugPlusOne => Difficulty::Hard,
+        Technique::Skyscraper | Technique::TwoStringKite => Difficulty::Hard,
+        Technique::XYWing | Technique::XYZWing | Technique::WWing | Technique::SimpleColoring => Difficulty::Hard,
+        Technique::FinnedXWing | Technique::NakedQuad | Technique::HiddenQuad => Difficulty::Hard,
+        Technique::FinnedSwordfish | Technique::Jellyfish | Technique::FinnedJellyfish => Difficulty::Expert,
+        Technique::XChain | Technique::XYChain | Technique::NiceLoop | Technique::Aic => Difficulty::Expert,
+        Technique::AlsXZ | Technique::AlsXYWing => Difficulty::Extreme,
+        Technique::CellForcingChain | Technique::UnitForcingChain => Difficulty::Extreme,
+    }
+}
+
+
+// rates a puzzle by the hardest technique it needs and by how many steps of which kind it takes
+pub fn rate<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize>(sudoku: &Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>) -> Rating
+where
+    Symbols<N_ROWS>: DefaultStorage,
+{
+    let solution = LogicalSolver::new(sudoku).solve();
+
+    // the enum is in the order the solver tries the techniques, so compare by score
+    let hardest = solution.steps.iter().map(|step| step.technique).max_by_key(|&technique| technique_score(technique));
+    let needed = solution.steps.iter().map(|step| technique_difficulty(step.technique)).max();
+    let mut score: u32 = solution.steps.iter().map(|step| technique_score(step.technique)).sum();
+
+    let solved = solution.outcome == LogicalOutcome::Solved;
+    if !solved {
+        score += STUCK_PENALTY;
+    }
+
+    let by_score = SCORE_LIMITS.iter()
+        .find(|(limit, _)| score <= *limit)
+        .map_or(Difficulty::Extreme, |&(_, difficulty)| difficulty);
+
+    let difficulty = if solved {
+        by_score.max(needed.unwrap_or(Difficulty::Easy))
+    } else {
+        Difficulty::Extreme
+    };
+
+    Rating { score, difficulty, hardest, solved }
+}
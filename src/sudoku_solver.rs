@@ -0,0 +1,241 @@
+use crate::sudoku::Sudoku;
+use crate::sudoku_candidate_storage::{CandidateStorage, DefaultStorage, StorageFor, Symbols};
+use crate::sudoku_candidates::Candidates;
+use crate::sudoku_iterator::{SudokuIterator, SudokuIteratorMode, Unit};
+use crate::sudoku_pencil_notes::{HiddenSingleIterator, PencilNotes};
+
+
+// The board being filled together with the candidates that are still open for it.
+// Every placement goes through `place`, so both always describe the same position.
+#[derive(Debug, Clone)]
+pub(crate) struct SolverState<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize = 3, const BOX_W: usize = 3, S: CandidateStorage = StorageFor<N_ROWS>>
+{
+    pub sudoku: Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>,
+    pub pencil_notes: PencilNotes<N_ROWS, N_COLS, BOX_H, BOX_W, S>,
+}
+
+
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize, S: CandidateStorage> SolverState<N_ROWS, N_COLS, BOX_H, BOX_W, S> {
+
+    // None if a given is not a number of the grid
+    pub fn new(sudoku: &Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>) -> Option<Self>
+    {
+        let sudoku = Sudoku::try_new(sudoku.board).ok()?;
+
+        Some(SolverState { sudoku, pencil_notes: PencilNotes::from_sudoku(&sudoku) })
+    }
+
+    pub fn empty() -> Self
+    {
+        SolverState { sudoku: Sudoku::new([[0; N_COLS]; N_ROWS]), pencil_notes: PencilNotes::new() }
+    }
+
+    pub fn place(&mut self, row: usize, col: usize, number: u32)
+    {
+        self.sudoku.board[row][col] = number;
+        self.pencil_notes.set_possibility(row, col, number);
+        self.pencil_notes.eliminate_possibility(row, col, number);
+    }
+
+    // every unit needs a place left for every number, otherwise this position is dead
+    pub fn is_consistent(&self) -> bool
+    {
+        if !self.pencil_notes.check() {
+            return false;
+        }
+
+        let all_numbers = Candidates::<S>::all(self.sudoku.max_number());
+
+        for (_, cells) in self.pencil_notes.geometry().units() {
+
+            let seen = cells.iter().fold(Candidates::new(), |seen, &(r, c)| seen | self.pencil_notes.get_possibilities(r, c));
+
+            if seen != all_numbers {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    // places naked and hidden singles until nothing changes anymore.
+    // returns false as soon as a contradiction shows up.
+    pub fn propagate(&mut self) -> bool
+    {
+        loop {
+            if !self.is_consistent() {
+                return false;
+            }
+
+            let mut progress = false;
+
+            for (r, c) in SudokuIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(0, 0, SudokuIteratorMode::All) {
+
+                if self.sudoku.board[r][c] != 0 {
+                    continue;
+                }
+
+                if let Some(number) = self.pencil_notes.get_possibility(r, c) {
+                    self.place(r, c, number);
+                    progress = true;
+                }
+            }
+
+            for unit in Unit::all::<N_ROWS, N_COLS, BOX_H, BOX_W>() {
+
+                let (row, col, mode) = unit.to_mode::<N_ROWS, N_COLS, BOX_H, BOX_W>();
+                for (r, c, number) in HiddenSingleIterator::<N_ROWS, N_COLS, BOX_H, BOX_W>::new(&self.pencil_notes, row, col, mode) {
+
+                    // an earlier placement in this pass may already have taken the number away
+                    if self.sudoku.board[r][c] == 0 && self.pencil_notes.has_possibility(r, c, number) {
+                        self.place(r, c, number);
+                        progress = true;
+                    }
+                }
+            }
+
+            if !progress {
+                return true;
+            }
+        }
+    }
+}
+
+
+// depth first search: propagate, then branch on the cell with the fewest candidates.
+// every solution is handed to `found`, which returns true to stop the search.
+fn search<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize, S: CandidateStorage>(mut state: SolverState<N_ROWS, N_COLS, BOX_H, BOX_W, S>, found: &mut dyn FnMut(&Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>) -> bool) -> bool
+{
+    if !state.propagate() {
+        return false;
+    }
+
+    let Some((row, col)) = state.pencil_notes.find_lowest_entropy_cell() else {
+        // no open cell left after propagation, the board is complete
+        return found(&state.sudoku);
+    };
+
+    for number in state.pencil_notes.get_possibilities(row, col) {
+
+        let mut next = state.clone();
+        next.place(row, col, number);
+
+        if search(next, found) {
+            return true;
+        }
+    }
+
+    false
+}
+
+
+// A way of solving sudokus, so the backends can be swapped behind the same API.
+pub trait SudokuSolver<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize = 3, const BOX_W: usize = 3>
+{
+    // returns the first solution found, or None if the givens cannot be completed
+    fn solve(&self, sudoku: &Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>) -> Option<Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>>;
+
+    // counts the solutions, but stops looking once `limit` of them are found
+    fn count_solutions(&self, sudoku: &Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>, limit: usize) -> usize;
+}
+
+
+// Depth first search over the pencil notes, propagating singles before every guess.
+// The pencil notes use the storage that fits the grid, see DefaultStorage.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BacktrackingSolver;
+
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize> SudokuSolver<N_ROWS, N_COLS, BOX_H, BOX_W> for BacktrackingSolver
+where
+    Symbols<N_ROWS>: DefaultStorage,
+{
+
+    fn solve(&self, sudoku: &Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>) -> Option<Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>>
+    {
+        let mut solution = None;
+
+        let state = SolverState::<N_ROWS, N_COLS, BOX_H, BOX_W, StorageFor<N_ROWS>>::new(sudoku)?;
+        search(state, &mut |sudoku| {
+            solution = Some(*sudoku);
+            true
+        });
+
+        solution
+    }
+
+    fn count_solutions(&self, sudoku: &Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>, limit: usize) -> usize
+    {
+        let mut count = 0;
+
+        let Some(state) = SolverState::<N_ROWS, N_COLS, BOX_H, BOX_W, StorageFor<N_ROWS>>::new(sudoku) else {
+            return count;
+        };
+
+        if limit == 0 {
+            return count;
+        }
+
+        search(state, &mut |_| {
+            count += 1;
+            count >= limit
+        });
+
+        count
+    }
+}
+
+
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize> Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W>
+where
+    Symbols<N_ROWS>: DefaultStorage,
+{
+
+    // returns the first solution found, or None if the givens cannot be completed
+    pub fn solve(&self) -> Option<Self>
+    {
+        self.solve_with(&BacktrackingSolver)
+    }
+
+    // counts the solutions, but stops looking once `limit` of them are found
+    pub fn count_solutions(&self, limit: usize) -> usize
+    {
+        self.count_solutions_with(&BacktrackingSolver, limit)
+    }
+
+    pub fn has_unique_solution(&self) -> bool
+    {
+        self.count_solutions(2) == 1
+    }
+
+    // the solution if there is exactly one. a single search counting up to two solutions
+    // that keeps the first, instead of has_unique_solution followed by solve
+    pub fn unique_solution(&self) -> Option<Self>
+    {
+        let state = SolverState::<N_ROWS, N_COLS, BOX_H, BOX_W, StorageFor<N_ROWS>>::new(self)?;
+
+        let mut first = None;
+        let mut count = 0;
+
+        search(state, &mut |sudoku| {
+            first.get_or_insert(*sudoku);
+            count += 1;
+            count >= 2
+        });
+
+        if count == 1 { first } else { None }
+    }
+}
+
+
+impl<const N_ROWS: usize, const N_COLS: usize, const BOX_H: usize, const BOX_W: usize> Sudoku<N_ROWS, N_COLS, BOX_H, BOX_W> {
+
+    pub fn solve_with(&self, solver: &dyn SudokuSolver<N_ROWS, N_COLS, BOX_H, BOX_W>) -> Option<Self>
+    {
+        solver.solve(self)
+    }
+
+    pub fn count_solutions_with(&self, solver: &dyn SudokuSolver<N_ROWS, N_COLS, BOX_H, BOX_W>, limit: usize) -> usize
+    {
+        solver.count_solutions(self, limit)
+    }
+}
@@ -0,0 +1,32 @@
+// helpers shared by the integration tests, not every test file uses all of them
+#![allow(dead_code)]
+
+use sudoku::sudoku_candidate_storage::CandidateStorage;
+use sudoku::sudoku_candidates::Candidates;
+
+
+// the puzzle from the Wikipedia sudoku article
+pub const PUZZLE: [[u32; 9]; 9] = [
+     [5, 3, 0, 0, 7, 0, 0, 0, 0]
+    ,[6, 0, 0, 1, 9, 5, 0, 0, 0]
+    ,[0, 9, 8, 0, 0, 0, 0, 6, 0]
+    ,[8, 0, 0, 0, 6, 0, 0, 0, 3]
+    ,[4, 0, 0, 8, 0, 3, 0, 0, 1]
+    ,[7, 0, 0, 0, 2, 0, 0, 0, 6]
+    ,[0, 6, 0, 0, 0, 0, 2, 8, 0]
+    ,[0, 0, 0, 4, 1, 9, 0, 0, 5]
+    ,[0, 0, 0, 0, 8, 0, 0, 7, 9]];
+
+
+// the candidate bits of `numbers`, as stored in PencilNotes::possibilities
+pub fn mask<S: CandidateStorage>(numbers: &[u32]) -> S {
+
+    Candidates::<S>::from_numbers(numbers).bits()
+}
+
+
+pub fn sorted(mut eliminations: Vec<(usize, usize, u32)>) -> Vec<(usize, usize, u32)> {
+
+    eliminations.sort();
+    eliminations
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use sudoku::sudoku_iterator::Unit;
+use sudoku::sudoku_pencil_notes::PencilNotes;
+use sudoku::sudoku_pencil_notes_aic::{AicKind, ForcingKind};
+use sudoku::sudoku_pencil_notes_chains::LinkKind;
+
+use common::{mask, sorted};
+
+
+#[test]
+fn test_xy_chain() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    pencil_notes.possibilities[0][0] = mask(&[1, 2]);
+    pencil_notes.possibilities[0][4] = mask(&[2, 3]);
+    pencil_notes.possibilities[4][4] = mask(&[3, 4]);
+    pencil_notes.possibilities[4][1] = mask(&[1, 4]);
+
+    let aic = pencil_notes.handle_aic(AicKind::XYChain, 12).expect("found the xy-chain");
+
+    assert_eq!(aic.nodes, vec![(0, 0, 1), (0, 0, 2), (0, 4, 2), (0, 4, 3), (4, 4, 3), (4, 4, 4), (4, 1, 4), (4, 1, 1)]);
+    assert_eq!(aic.links.iter().map(|link| link.kind).collect::<Vec<_>>(),
+               vec![LinkKind::Strong, LinkKind::Weak, LinkKind::Strong, LinkKind::Weak, LinkKind::Strong, LinkKind::Weak, LinkKind::Strong]);
+    assert_eq!(sorted(aic.eliminations), vec![(0, 1, 1), (1, 1, 1), (2, 1, 1), (3, 0, 1), (4, 0, 1), (5, 0, 1)]);
+    assert!(!pencil_notes.has_possibility(4, 0, 1));
+
+    // too short to reach the other end
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    pencil_notes.possibilities[0][0] = mask(&[1, 2]);
+    pencil_notes.possibilities[0][4] = mask(&[2, 3]);
+    pencil_notes.possibilities[4][4] = mask(&[3, 4]);
+    pencil_notes.possibilities[4][1] = mask(&[1, 4]);
+
+    assert_eq!(pencil_notes.find_aic(AicKind::XYChain, 5), None);
+}
+
+
+#[test]
+fn test_nice_loop() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    pencil_notes.possibilities[0][0] = mask(&[1, 2]);
+    pencil_notes.possibilities[0][4] = mask(&[2, 3]);
+    pencil_notes.possibilities[4][4] = mask(&[3, 4]);
+    pencil_notes.possibilities[4][0] = mask(&[1, 4]);
+
+    let aic = pencil_notes.handle_aic(AicKind::NiceLoop, 12).expect("found the loop");
+
+    assert_eq!(aic.nodes.len(), 8);
+    assert_eq!(aic.links.len(), 8);
+    assert_eq!(aic.links.last().unwrap().to, (0, 0, 1));
+
+    // every weak link of the loop clears its unit
+    assert_eq!(aic.eliminations.len(), 28);
+    for c in [1, 2, 3, 5, 6, 7, 8] {
+        assert!(!pencil_notes.has_possibility(0, c, 2));
+        assert!(!pencil_notes.has_possibility(4, c, 4));
+    }
+    for r in [1, 2, 3, 5, 6, 7, 8] {
+        assert!(!pencil_notes.has_possibility(r, 4, 3));
+        assert!(!pencil_notes.has_possibility(r, 0, 1));
+    }
+}
+
+
+#[test]
+fn test_aic() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    pencil_notes.possibilities[0][0] = mask(&[1, 2]);
+    pencil_notes.possibilities[6][7] = mask(&[1, 2]);
+
+    // 2 can only go to (0, 5) and (6, 5) in column 5
+    for r in 1..9 {
+        if r != 6 {
+            pencil_notes.remove_possibility(r, 5, 2);
+        }
+    }
+
+    assert_eq!(pencil_notes.find_aic(AicKind::XYChain, 12), None);
+
+    let aic = pencil_notes.handle_aic(AicKind::Aic, 12).expect("found the chain");
+
+    assert_eq!(aic.nodes, vec![(0, 0, 1), (0, 0, 2), (0, 5, 2), (6, 5, 2), (6, 7, 2), (6, 7, 1)]);
+    assert_eq!(sorted(aic.eliminations), vec![(0, 7, 1), (6, 0, 1)]);
+}
+
+
+#[test]
+fn test_cell_forcing_chain() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    pencil_notes.possibilities[0][0] = mask(&[1, 2, 3]);
+    pencil_notes.possibilities[0][4] = mask(&[1, 5]);
+    pencil_notes.possibilities[4][0] = mask(&[2, 5]);
+    pencil_notes.possibilities[0][8] = mask(&[3, 6]);
+    pencil_notes.possibilities[4][8] = mask(&[5, 6]);
+
+    // the premise 3 needs five links to get to (4, 4)
+    assert_eq!(pencil_notes.find_forcing_chain(ForcingKind::Cell, 3), None);
+
+    let forcing_chain = pencil_notes.find_forcing_chain(ForcingKind::Cell, 12).expect("found the forcing chain");
+
+    assert_eq!(forcing_chain.unit, None);
+    assert_eq!(forcing_chain.premises, vec![(0, 0, 1), (0, 0, 2), (0, 0, 3)]);
+    assert_eq!(forcing_chain.placement, None);
+    assert_eq!(forcing_chain.eliminations, vec![(4, 4, 5)]);
+    assert_eq!(forcing_chain.chains.iter().map(|chain| chain.len()).collect::<Vec<_>>(), vec![3, 3, 5]);
+    assert!(forcing_chain.chains.iter().all(|chain| chain.last().unwrap().to == (4, 4, 5)));
+}
+
+
+#[test]
+fn test_unit_forcing_chain() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+
+    // 7 can only go to (0, 0), (0, 4) and (0, 8) in row 0
+    for c in [1, 2, 3, 5, 6, 7] {
+        pencil_notes.remove_possibility(0, c, 7);
+    }
+    pencil_notes.possibilities[4][0] = mask(&[7, 5]);
+    pencil_notes.possibilities[4][8] = mask(&[7, 5]);
+    pencil_notes.possibilities[3][4] = mask(&[7, 5]);
+
+    let forcing_chain = pencil_notes.find_forcing_chain(ForcingKind::Unit, 12).expect("found the forcing chain");
+
+    assert_eq!(forcing_chain.unit, Some(Unit::Row(0)));
+    assert_eq!(forcing_chain.premises, vec![(0, 0, 7), (0, 4, 7), (0, 8, 7)]);
+    // (4, 3), (4, 4) and (4, 5) see all three 5s, the first one is reported
+    assert_eq!(forcing_chain.eliminations, vec![(4, 3, 5)]);
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use sudoku::sudoku_iterator::Unit;
+use sudoku::sudoku_pencil_notes::PencilNotes;
+use sudoku::sudoku_pencil_notes_als::{AlsKind, MAX_ALS_SIZE};
+
+use common::{mask, sorted};
+
+
+#[test]
+fn test_find_als() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    pencil_notes.possibilities[3][0] = mask(&[1, 3]);
+    pencil_notes.possibilities[4][1] = mask(&[2, 3]);
+
+    let sets = pencil_notes.find_als();
+
+    // a bivalue cell is an almost locked set on its own
+    assert!(sets.iter().any(|set| set.cells == vec![(3, 0)] && set.candidates == mask(&[1, 3])));
+
+    let pair = sets.iter().find(|set| set.cells == vec![(3, 0), (4, 1)]).expect("found the pair");
+    assert_eq!(pair.unit, Unit::Square(3));
+    assert_eq!(pair.candidates, mask(&[1, 2, 3]));
+}
+
+
+#[test]
+fn test_als_xz() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    pencil_notes.possibilities[0][0] = mask(&[1, 2]);
+    pencil_notes.possibilities[3][0] = mask(&[1, 3]);
+    pencil_notes.possibilities[4][1] = mask(&[2, 3]);
+
+    let chain = pencil_notes.handle_als_chain(AlsKind::AlsXZ).expect("found the als-xz");
+
+    assert_eq!(chain.sets.len(), 2);
+    assert_eq!(chain.restricted, vec![1]);
+    assert_eq!(chain.number, 2);
+    assert_eq!(sorted(chain.eliminations), vec![(0, 1, 2), (1, 1, 2), (2, 1, 2), (4, 0, 2), (5, 0, 2)]);
+    assert!(!pencil_notes.has_possibility(4, 0, 2));
+}
+
+
+#[test]
+fn test_als_xy_wing() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    pencil_notes.possibilities[4][4] = mask(&[1, 2]);
+    pencil_notes.possibilities[0][4] = mask(&[2, 3]);
+    pencil_notes.possibilities[4][0] = mask(&[1, 5]);
+    pencil_notes.possibilities[5][0] = mask(&[3, 5]);
+
+    let chain = pencil_notes.handle_als_chain(AlsKind::AlsXYWing).expect("found the als-xy-wing");
+
+    assert_eq!(chain.sets.len(), 3);
+    assert_eq!(chain.restricted.len(), 2);
+    assert_eq!(chain.number, 3);
+    assert_eq!(sorted(chain.eliminations), vec![(0, 0, 3), (5, 4, 3)]);
+}
+
+
+#[test]
+fn test_find_als_large_grid() {
+
+    // without the size cap every subset of the row would be tried: 2^25 of them
+    let mut pencil_notes = PencilNotes::<25, 25, 5, 5>::new();
+    for c in 0..25 {
+        pencil_notes.possibilities[0][c] = mask(&[1, 2]);
+    }
+
+    let sets = pencil_notes.find_als();
+
+    assert!(sets.iter().all(|set| set.cells.len() <= MAX_ALS_SIZE));
+    // only the bivalue cells, each listed once although it is in a row, a column and a square
+    assert_eq!(sets.len(), 25);
+}
@@ -0,0 +1,58 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use sudoku::sudoku_batch::Batch;
+use sudoku::sudoku_factory::Symmetry;
+use sudoku::sudoku_pencil_notes::RandomBit;
+use sudoku::sudoku_rating::rate;
+
+
+fn random_bit(seed: u64) -> RandomBit {
+
+    RandomBit::new(Box::new(StdRng::seed_from_u64(seed)))
+}
+
+
+#[test]
+fn test_batch_map_keeps_order() {
+
+    let items: Vec<usize> = (0..100).collect();
+
+    let squares = Batch::with_threads(4).map(&items, |&item| item * item);
+
+    assert_eq!(squares, items.iter().map(|item| item * item).collect::<Vec<_>>());
+    assert!(Batch::with_threads(4).map(&[] as &[usize], |&item| item).is_empty());
+}
+
+
+#[test]
+fn test_batch_generation_is_reproducible() {
+
+    // the same seed gives the same puzzles, no matter how many threads did the work
+    let single = Batch::with_threads(1).generate::<9, 9, 3, 3>(8, &mut random_bit(3), 0, Symmetry::None);
+    let many = Batch::with_threads(4).generate::<9, 9, 3, 3>(8, &mut random_bit(3), 0, Symmetry::None);
+
+    assert_eq!(single, many);
+    assert_eq!(single.len(), 8);
+
+    for puzzle in &single {
+        assert!(puzzle.puzzle.has_unique_solution());
+    }
+}
+
+
+#[test]
+fn test_batch_solve_and_rate() {
+
+    let batch = Batch::with_threads(3);
+    let generated = batch.generate_and_rate::<9, 9, 3, 3>(6, &mut random_bit(11), 0, Symmetry::Rotational);
+
+    let solutions = batch.solve(generated.iter().map(|(puzzle, _)| puzzle.puzzle));
+    let ratings = batch.rate(generated.iter().map(|(puzzle, _)| puzzle.puzzle));
+
+    for (((puzzle, rating), solution), rerated) in generated.iter().zip(solutions).zip(ratings) {
+        assert_eq!(solution, Some(puzzle.solution));
+        assert_eq!(*rating, rerated);
+        assert_eq!(rerated, rate(&puzzle.puzzle));
+    }
+}
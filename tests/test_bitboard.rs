@@ -0,0 +1,61 @@
+mod common;
+
+use sudoku::sudoku::Sudoku;
+use sudoku::sudoku_bitboard::Bitboard;
+
+use common::PUZZLE;
+
+
+#[test]
+fn test_bitboard_new()
+{
+    let board = Bitboard::new(&Sudoku::<9,9>::new(PUZZLE)).expect("the givens fit together");
+
+    // a given is placed and taken out of its peers
+    assert_eq!(board.values[0], 5);
+    assert_eq!(board.candidates[0], 1 << 4);
+    assert_eq!(board.candidates[2] & (1 << 4), 0);
+
+    // colliding givens, and a cell left without a number
+    let mut board = PUZZLE;
+    board[0][2] = 5;
+    assert_eq!(Bitboard::new(&Sudoku::<9,9>::new(board)), None);
+
+    let mut board = [[0; 9]; 9];
+    board[0] = [1, 2, 3, 4, 5, 6, 7, 8, 0];
+    board[4][8] = 9;
+    assert_eq!(Bitboard::new(&Sudoku::<9,9>::new(board)), None);
+}
+
+
+#[test]
+fn test_bitboard_propagate_singles()
+{
+    // the puzzle needs nothing but singles
+    let sudoku = Sudoku::<9,9>::new(PUZZLE);
+    let mut board = Bitboard::new(&sudoku).unwrap();
+
+    assert!(board.propagate());
+    assert_eq!(board.open_cell(), None);
+    assert_eq!(Some(board.to_sudoku()), sudoku.solve());
+}
+
+
+#[test]
+fn test_bitboard_propagate_hidden_single()
+{
+    // the 1s in rows 1 and 2 and columns 1 and 2 leave (0, 0) as the only place for 1 in the first square
+    let mut givens = [[0; 9]; 9];
+    givens[1][5] = 1;
+    givens[2][8] = 1;
+    givens[3][1] = 1;
+    givens[6][2] = 1;
+
+    let mut board = Bitboard::new(&Sudoku::<9,9>::new(givens)).unwrap();
+    assert_eq!(board.candidates[0].count_ones(), 9);
+
+    assert!(board.propagate());
+    assert_eq!(board.values[0], 1);
+    assert_eq!(board.candidates[0], 1);
+    assert!(board.open_cell().is_some());
+}
@@ -0,0 +1,115 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use sudoku::sudoku::Sudoku;
+use sudoku::sudoku_candidates::Candidates;
+use sudoku::sudoku_dlx::DlxSolver;
+use sudoku::sudoku_factory::{SudokuFactory, Symmetry};
+use sudoku::sudoku_iterator::{SudokuIterator, SudokuIteratorMode, Unit};
+use sudoku::sudoku_pencil_notes::PencilNotes;
+
+
+#[test]
+fn test_4x4() {
+
+    let sudoku = Sudoku::<4, 4, 2, 2>::new([
+         [1, 0, 0, 0]
+        ,[0, 0, 3, 0]
+        ,[0, 4, 0, 0]
+        ,[0, 0, 0, 2]]);
+
+    assert_eq!(sudoku.max_number(), 4);
+
+    let solution = sudoku.solve().expect("puzzle has a solution");
+    assert!(solution.is_complete() && solution.is_valid());
+    assert_eq!(solution.board[0], [1, 3, 2, 4]);
+    assert_eq!(sudoku.solve_with(&DlxSolver), Some(solution));
+
+    // the boxes are 2x2, so the separators come after every second row and column
+    let expected = "\
++-------+-------+
+| 1   3 | 2   4 |
+| 4   2 | 3   1 |
++-------+-------+
+| 2   4 | 1   3 |
+| 3   1 | 4   2 |
++-------+-------+
+";
+    assert_eq!(solution.to_string(), expected);
+}
+
+
+#[test]
+fn test_6x6() {
+
+    // boxes are two rows high and three columns wide
+    let square: Vec<(usize, usize)> = SudokuIterator::<6, 6, 2, 3>::new(3, 4, SudokuIteratorMode::Square).collect();
+    assert_eq!(square, vec![(2, 3), (2, 4), (2, 5), (3, 3), (3, 4), (3, 5)]);
+
+    assert_eq!(Unit::all::<6, 6, 2, 3>().len(), 18);
+    assert_eq!(Unit::from_mode::<6, 6, 2, 3>(3, 4, SudokuIteratorMode::Square), Some(Unit::Square(3)));
+    assert!(SudokuIterator::<6, 6, 2, 3>::sees(2, 0, 3, 2));
+    assert!(!SudokuIterator::<6, 6, 2, 3>::sees(1, 0, 2, 2));
+
+    // same box, different row and column
+    let mut board = [[0; 6]; 6];
+    board[0][0] = 1;
+    board[1][2] = 1;
+    assert!(!Sudoku::<6, 6, 2, 3>::new(board).is_valid());
+
+    let mut factory = SudokuFactory::<6, 6, 2, 3>::new(Box::new(StdRng::seed_from_u64(6)));
+    for _ in 0..5 {
+        let puzzle = factory.generate_puzzle(0, Symmetry::Rotational);
+
+        assert!(puzzle.solution.is_complete() && puzzle.solution.is_valid());
+        assert!(puzzle.puzzle.has_unique_solution());
+        assert_eq!(puzzle.puzzle.solve_with(&DlxSolver), Some(puzzle.solution));
+    }
+
+    let pencil_notes = PencilNotes::<6, 6, 2, 3>::new();
+    assert_eq!(pencil_notes.get_possibilities(0, 0), Candidates::all(6));
+}
+
+
+#[test]
+fn test_16x16() {
+
+    let mut factory = SudokuFactory::<16, 16, 4, 4>::new(Box::new(StdRng::seed_from_u64(16)));
+    let solution = factory.generate();
+
+    assert!(solution.is_complete() && solution.is_valid());
+    assert_eq!(solution.max_number(), 16);
+
+    // take out every other cell and solve it back
+    let mut puzzle = solution;
+    for r in 0..16 {
+        for c in 0..16 {
+            if (r + c) % 2 == 0 {
+                puzzle.board[r][c] = 0;
+            }
+        }
+    }
+
+    let solved = puzzle.solve_with(&DlxSolver).expect("puzzle has a solution");
+    assert!(solved.is_complete() && solved.is_valid());
+}
+
+
+#[test]
+fn test_25x25() {
+
+    let empty = Sudoku::<25, 25, 5, 5>::new([[0; 25]; 25]);
+
+    let solution = empty.solve_with(&DlxSolver).expect("an empty grid has a solution");
+    assert!(solution.is_complete() && solution.is_valid());
+    assert!(solution.board.iter().flatten().all(|&number| (1..=25).contains(&number)));
+
+    let square: Vec<(usize, usize)> = Unit::Square(24).iter::<25, 25, 5, 5>().collect();
+    assert_eq!(square.first(), Some(&(20, 20)));
+    assert_eq!(square.len(), 25);
+
+    let mut pencil_notes = PencilNotes::<25, 25, 5, 5>::from_sudoku(&solution);
+    assert!(pencil_notes.check());
+    pencil_notes.reset();
+    assert_eq!(pencil_notes.count_possibilities(24, 24), 25);
+}
@@ -0,0 +1,168 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use sudoku::sudoku::Sudoku;
+use sudoku::sudoku_candidate_storage::{CandidateStorage, StorageFor};
+use sudoku::sudoku_iterator::SudokuIteratorMode;
+use sudoku::sudoku_pencil_notes::{HiddenSingleIterator, PencilNotes, PossibilityIterator, RandomBit};
+
+
+// a valid grid from the usual shifting pattern, rows of a band are shifted by the box width
+fn pattern<const N: usize, const BOX_H: usize, const BOX_W: usize>() -> Sudoku<N, N, BOX_H, BOX_W> {
+
+    let mut board = [[0; N]; N];
+    for (r, row) in board.iter_mut().enumerate() {
+        for (c, cell) in row.iter_mut().enumerate() {
+            *cell = ((BOX_W * (r % BOX_H) + r / BOX_H + c) % N) as u32 + 1;
+        }
+    }
+
+    Sudoku::new(board)
+}
+
+
+fn storage_basics<S: CandidateStorage>() {
+
+    assert_eq!(S::lowest(S::BITS).count_ones(), S::BITS);
+    assert_eq!(S::lowest(5).count_ones(), 5);
+    assert!(S::EMPTY.is_empty());
+
+    let top = S::bit(S::BITS - 1);
+    assert_eq!(top.trailing_zeros(), S::BITS - 1);
+    assert!((top | S::bit(3)).has_bit(3));
+    assert_eq!((top | S::bit(3)).without_lowest(), top);
+
+    assert_eq!(PossibilityIterator::new(top | S::bit(0)).collect::<Vec<_>>(), vec![1, S::BITS]);
+}
+
+
+#[test]
+fn test_storage_types() {
+
+    storage_basics::<u16>();
+    storage_basics::<u32>();
+    storage_basics::<u64>();
+    storage_basics::<u128>();
+
+    let mut random_bit = RandomBit::new(Box::new(StdRng::seed_from_u64(42)));
+    assert_eq!(random_bit.select_random_candidate(1u128 << 100), Some(100));
+    assert_eq!(random_bit.select_random_candidate(0u64), None);
+}
+
+
+#[test]
+fn test_wide_storage_matches_default() {
+
+    let mut board = pattern::<9, 3, 3>().board;
+    for (r, row) in board.iter_mut().enumerate() {
+        for (c, cell) in row.iter_mut().enumerate() {
+            if (r * 9 + c) % 3 != 0 {
+                *cell = 0;
+            }
+        }
+    }
+    let sudoku = Sudoku::<9, 9>::new(board);
+
+    // a 9x9 grid defaults to u16
+    let mut default = PencilNotes::<9, 9>::from_sudoku(&sudoku);
+    let mut wide = PencilNotes::<9, 9, 3, 3, u32>::from_sudoku(&sudoku);
+
+    for r in 0..9 {
+        for c in 0..9 {
+            assert_eq!(default.get_possibilities(r, c).bits() as u32, wide.get_possibilities(r, c).bits());
+        }
+    }
+
+    for row in 0..9 {
+        assert_eq!(default.handle_hidden_pairs(row, 0, SudokuIteratorMode::Column), wide.handle_hidden_pairs(row, 0, SudokuIteratorMode::Column));
+        assert_eq!(default.handle_naked_pairs(row, 0, SudokuIteratorMode::Column), wide.handle_naked_pairs(row, 0, SudokuIteratorMode::Column));
+    }
+
+    assert_eq!(default.to_string(), wide.to_string());
+}
+
+
+#[test]
+fn test_36x36() {
+
+    let solution = pattern::<36, 6, 6>();
+    assert!(solution.is_valid());
+
+    // every other cell of the first row is left open: each of them is a hidden single in the row
+    let mut puzzle = solution;
+    for c in (0..36).step_by(2) {
+        puzzle.board[0][c] = 0;
+    }
+
+    let pencil_notes = PencilNotes::<36, 36, 6, 6, u64>::from_sudoku(&puzzle);
+    assert!(pencil_notes.check());
+
+    let singles: Vec<(usize, usize, u32)> = HiddenSingleIterator::new(&pencil_notes, 0, 0, SudokuIteratorMode::Column)
+        .filter(|&(r, c, _)| puzzle.board[r][c] == 0)
+        .collect();
+    assert_eq!(singles.len(), 18);
+    for (r, c, number) in singles {
+        assert_eq!(solution.board[r][c], number);
+    }
+}
+
+
+#[test]
+fn test_49x49() {
+
+    let solution = pattern::<49, 7, 7>();
+    assert!(solution.is_valid());
+
+    let mut puzzle = solution;
+    for r in 0..49 {
+        puzzle.board[r][(r * 3) % 49] = 0;
+    }
+
+    let mut pencil_notes = PencilNotes::<49, 49, 7, 7, u64>::from_sudoku(&puzzle);
+
+    // a single open cell per row and column: all of them are naked singles
+    for r in 0..49 {
+        let c = (r * 3) % 49;
+        assert_eq!(pencil_notes.get_possibility(r, c), Some(solution.board[r][c]));
+    }
+
+    pencil_notes.reset();
+    assert_eq!(pencil_notes.count_possibilities(48, 48), 49);
+}
+
+
+#[test]
+fn test_36x36_solve() {
+
+    assert_eq!(<StorageFor<9> as CandidateStorage>::BITS, 16);
+    assert_eq!(<StorageFor<16> as CandidateStorage>::BITS, 16);
+    assert_eq!(<StorageFor<25> as CandidateStorage>::BITS, 32);
+    assert_eq!(<StorageFor<36> as CandidateStorage>::BITS, 64);
+    assert_eq!(<StorageFor<100> as CandidateStorage>::BITS, 128);
+
+    // the default storage of the pencil notes follows the grid size as well
+    let pencil_notes = PencilNotes::<36, 36, 6, 6>::new();
+    assert_eq!(pencil_notes.count_possibilities(35, 35), 36);
+
+    let solution = pattern::<36, 6, 6>();
+
+    let mut puzzle = solution;
+    for r in 0..36 {
+        for c in 0..36 {
+            if (r * 7 + c) % 4 == 0 {
+                puzzle.board[r][c] = 0;
+            }
+        }
+    }
+
+    let solved = puzzle.solve().expect("the puzzle has a solution");
+    assert!(solved.is_complete() && solved.is_valid());
+
+    for r in 0..36 {
+        for c in 0..36 {
+            if puzzle.board[r][c] != 0 {
+                assert_eq!(solved.board[r][c], puzzle.board[r][c]);
+            }
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use sudoku::sudoku::Sudoku;
+use sudoku::sudoku_candidates::Candidates;
+use sudoku::sudoku_pencil_notes::{PencilNotes, RandomBit};
+
+
+#[test]
+fn test_set_algebra() {
+
+    let a = Candidates::<u32>::from_numbers(&[1, 2, 3, 5]);
+    let b = Candidates::<u32>::from_numbers(&[3, 5, 9]);
+
+    assert_eq!(a | b, Candidates::from_numbers(&[1, 2, 3, 5, 9]));
+    assert_eq!(a & b, Candidates::from_numbers(&[3, 5]));
+    assert_eq!(a - b, Candidates::from_numbers(&[1, 2]));
+    assert_eq!(b - a, Candidates::from_numbers(&[9]));
+
+    assert!((a & b).is_subset(a));
+    assert!(!a.is_subset(b));
+    assert!(Candidates::<u32>::new().is_subset(b));
+
+    assert_eq!(Candidates::<u32>::all(9).len(), 9);
+    assert_eq!(Candidates::<u16>::all(16).bits(), 0xffff);
+}
+
+
+#[test]
+fn test_members() {
+
+    let mut candidates = Candidates::<u64>::new();
+    assert!(candidates.is_empty());
+    assert_eq!(candidates.single(), None);
+
+    candidates.insert(36);
+    assert!(candidates.contains(36));
+    assert!(!candidates.contains(35));
+    assert_eq!(candidates.single(), Some(36));
+
+    candidates.insert(1);
+    assert_eq!(candidates.len(), 2);
+    assert_eq!(candidates.single(), None);
+    assert_eq!(candidates.iter().collect::<Vec<_>>(), vec![1, 36]);
+
+    candidates.remove(36);
+    assert_eq!(candidates.single(), Some(1));
+
+    let collected: Candidates = (4..=6).collect();
+    assert_eq!(collected.bits(), 0b111000);
+}
+
+
+#[test]
+fn test_out_of_range_numbers() {
+
+    let mut candidates = Candidates::<u16>::from_numbers(&[0, 1, 16, 17, 100]);
+    assert_eq!(candidates, Candidates::from_numbers(&[1, 16]));
+
+    assert!(!candidates.contains(0));
+    assert!(!candidates.contains(17));
+    assert!(!candidates.contains(u32::MAX));
+
+    candidates.remove(0);
+    candidates.remove(17);
+    assert_eq!(candidates.len(), 2);
+}
+
+
+#[test]
+fn test_display() {
+
+    assert_eq!(format!("{}", Candidates::<u32>::from_numbers(&[7, 1, 4])), "{1, 4, 7}");
+    assert_eq!(format!("{}", Candidates::<u32>::new()), "{}");
+    assert_eq!(format!("{:?}", Candidates::<u32>::from_numbers(&[2])), "Candidates{2}");
+}
+
+
+#[test]
+fn test_pencil_notes_return_candidates() {
+
+    let mut sudoku = Sudoku::<9, 9>::new([[0; 9]; 9]);
+    sudoku.board[0][1] = 1;
+    sudoku.board[1][0] = 2;
+    sudoku.board[4][4] = 3;
+
+    let pencil_notes = PencilNotes::<9, 9>::from_sudoku(&sudoku);
+
+    let possibilities = pencil_notes.get_possibilities(0, 0);
+    assert_eq!(possibilities, Candidates::all(9) - Candidates::from_numbers(&[1, 2]));
+    assert_eq!(possibilities.len() as u32, pencil_notes.count_possibilities(0, 0));
+    assert_eq!(pencil_notes.get_possibilities(0, 1).single(), Some(1));
+}
+
+
+#[test]
+fn test_random_number() {
+
+    let mut random_bit = RandomBit::new(Box::new(StdRng::seed_from_u64(42)));
+    let candidates = Candidates::<u16>::from_numbers(&[2, 7, 9]);
+
+    for _ in 0..20 {
+        let number = random_bit.select_random_number(candidates).expect("there are candidates");
+        assert!(candidates.contains(number));
+    }
+
+    assert_eq!(random_bit.select_random_number(Candidates::<u16>::from_numbers(&[16])), Some(16));
+    assert_eq!(random_bit.select_random_number(Candidates::<u16>::new()), None);
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use sudoku::sudoku_iterator::Unit;
+use sudoku::sudoku_pencil_notes::PencilNotes;
+use sudoku::sudoku_pencil_notes_chains::{ChainKind, LinkKind};
+
+use common::sorted;
+
+
+// leaves `number` only in the given cells of the unit
+fn restrict(pencil_notes: &mut PencilNotes<9, 9>, number: u32, unit: Unit, cells: &[(usize, usize)]) {
+
+    for (r, c) in unit.iter::<9, 9, 3, 3>() {
+        if !cells.contains(&(r, c)) {
+            pencil_notes.remove_possibility(r, c, number);
+        }
+    }
+}
+
+
+#[test]
+fn test_link_graph() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    restrict(&mut pencil_notes, 1, Unit::Row(1), &[(1, 2), (1, 4)]);
+
+    let graph = pencil_notes.link_graph(1);
+
+    assert_eq!(graph.cells.len(), 74);
+    assert_eq!(graph.link((1, 2), (1, 4)).unwrap().kind, LinkKind::Strong);
+    assert_eq!(graph.link((1, 2), (2, 2)).unwrap().kind, LinkKind::Weak);
+    assert_eq!(graph.link((1, 2), (5, 5)), None);
+    assert_eq!(graph.links_of((1, 4), LinkKind::Strong).count(), 1);
+}
+
+
+#[test]
+fn test_skyscraper() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    restrict(&mut pencil_notes, 1, Unit::Row(1), &[(1, 2), (1, 4)]);
+    restrict(&mut pencil_notes, 1, Unit::Row(6), &[(6, 2), (6, 5)]);
+
+    let chain = pencil_notes.handle_chain(ChainKind::Skyscraper).expect("found the skyscraper");
+
+    assert_eq!(chain.number, 1);
+    assert_eq!(chain.nodes, vec![((1, 4), false), ((1, 2), true), ((6, 2), false), ((6, 5), true)]);
+    assert_eq!(chain.links.iter().map(|link| link.kind).collect::<Vec<_>>(), vec![LinkKind::Strong, LinkKind::Weak, LinkKind::Strong]);
+    assert_eq!(chain.links[1].unit, Unit::Column(2));
+    assert_eq!(sorted(chain.eliminations), vec![(0, 5, 1), (2, 5, 1), (7, 4, 1), (8, 4, 1)]);
+
+    assert!(!pencil_notes.has_possibility(0, 5, 1));
+    assert_eq!(pencil_notes.find_chain(ChainKind::Skyscraper), None);
+}
+
+
+#[test]
+fn test_two_string_kite() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    restrict(&mut pencil_notes, 2, Unit::Row(0), &[(0, 1), (0, 6)]);
+    restrict(&mut pencil_notes, 2, Unit::Column(0), &[(2, 0), (7, 0)]);
+
+    assert_eq!(pencil_notes.find_chain(ChainKind::Skyscraper), None);
+
+    let chain = pencil_notes.handle_chain(ChainKind::TwoStringKite).expect("found the kite");
+
+    assert_eq!(chain.nodes.first().unwrap().0, (0, 6));
+    assert_eq!(chain.nodes.last().unwrap().0, (7, 0));
+    assert_eq!(chain.eliminations, vec![(7, 6, 2)]);
+    assert!(!pencil_notes.has_possibility(7, 6, 2));
+}
+
+
+#[test]
+fn test_simple_coloring() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    restrict(&mut pencil_notes, 5, Unit::Row(0), &[(0, 0), (0, 6)]);
+    restrict(&mut pencil_notes, 5, Unit::Column(6), &[(0, 6), (5, 6)]);
+    restrict(&mut pencil_notes, 5, Unit::Row(5), &[(5, 1), (5, 6)]);
+
+    let chain = pencil_notes.handle_chain(ChainKind::SimpleColoring).expect("found the coloring");
+
+    assert_eq!(chain.nodes, vec![((0, 0), false), ((0, 6), true), ((5, 6), false), ((5, 1), true)]);
+    assert_eq!(chain.links.len(), 3);
+    assert_eq!(sorted(chain.eliminations), vec![(1, 1, 5), (2, 1, 5), (3, 0, 5), (4, 0, 5)]);
+}
+
+
+#[test]
+fn test_x_chain() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    restrict(&mut pencil_notes, 3, Unit::Row(0), &[(0, 0), (0, 4)]);
+    restrict(&mut pencil_notes, 3, Unit::Row(1), &[(1, 4), (1, 8)]);
+    restrict(&mut pencil_notes, 3, Unit::Row(4), &[(4, 8), (4, 2)]);
+
+    assert_eq!(pencil_notes.find_chain(ChainKind::Skyscraper), None);
+    assert_eq!(pencil_notes.find_chain(ChainKind::SimpleColoring), None);
+
+    let chain = pencil_notes.handle_chain(ChainKind::XChain).expect("found the x-chain");
+
+    let cells: Vec<(usize, usize)> = chain.nodes.iter().map(|node| node.0).collect();
+    assert_eq!(cells, vec![(0, 0), (0, 4), (1, 4), (1, 8), (4, 8), (4, 2)]);
+    assert_eq!(chain.links.len(), 5);
+    assert_eq!(sorted(chain.eliminations), vec![(2, 2, 3), (3, 0, 3), (5, 0, 3)]);
+}
@@ -0,0 +1,47 @@
+use sudoku::sudoku_dlx::ExactCover;
+
+
+#[test]
+fn test_exact_cover()
+{
+    // Knuth's example from the dancing links paper, the only solution is rows 0, 3 and 4
+    let mut cover = ExactCover::new(7, 0);
+    cover.add_row(&[2, 4, 5]);
+    cover.add_row(&[0, 3, 6]);
+    cover.add_row(&[1, 2, 5]);
+    cover.add_row(&[0, 3]);
+    cover.add_row(&[1, 6]);
+    cover.add_row(&[3, 4, 6]);
+
+    let mut solutions = Vec::new();
+    cover.search(&mut |rows| {
+        let mut rows = rows.to_vec();
+        rows.sort();
+        solutions.push(rows);
+        false
+    });
+
+    assert_eq!(solutions, vec![vec![0, 3, 4]]);
+}
+
+
+#[test]
+fn test_exact_cover_secondary_columns()
+{
+    // column 2 may stay empty, but can't be covered twice
+    let mut cover = ExactCover::new(2, 1);
+    let a = cover.add_row(&[0, 2]);
+    let b = cover.add_row(&[1, 2]);
+    let c = cover.add_row(&[1]);
+
+    let mut solutions = Vec::new();
+    cover.search(&mut |rows| {
+        solutions.push(rows.to_vec());
+        false
+    });
+
+    assert_eq!(solutions, vec![vec![a, c]]);
+
+    assert!(cover.select(a));
+    assert!(!cover.select(b));
+}
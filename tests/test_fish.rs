@@ -0,0 +1,126 @@
+use sudoku::sudoku_iterator::Unit;
+use sudoku::sudoku_pencil_notes::PencilNotes;
+
+
+// leaves `number` only in the given columns of each listed row
+fn restrict_rows(pencil_notes: &mut PencilNotes<9, 9>, number: u32, rows: &[(usize, &[usize])]) {
+
+    for &(row, cols) in rows {
+        for c in 0..9 {
+            if !cols.contains(&c) {
+                pencil_notes.remove_possibility(row, c, number);
+            }
+        }
+    }
+}
+
+
+#[test]
+fn test_x_wing() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    restrict_rows(&mut pencil_notes, 1, &[(1, &[2, 7]), (5, &[2, 7])]);
+
+    let fish = pencil_notes.handle_fish(2, false).expect("found the x-wing");
+
+    assert_eq!(fish.number, 1);
+    assert_eq!(fish.base, vec![Unit::Row(1), Unit::Row(5)]);
+    assert_eq!(fish.cover, vec![Unit::Column(2), Unit::Column(7)]);
+    assert!(fish.fins.is_empty());
+    assert_eq!(fish.eliminations.len(), 14);
+
+    for r in [0, 2, 3, 4, 6, 7, 8] {
+        assert!(!pencil_notes.has_possibility(r, 2, 1));
+        assert!(!pencil_notes.has_possibility(r, 7, 1));
+    }
+    assert!(pencil_notes.has_possibility(1, 2, 1));
+    assert!(pencil_notes.has_possibility(0, 3, 1));
+
+    // nothing left to eliminate
+    assert_eq!(pencil_notes.find_fish(2, false), None);
+}
+
+
+#[test]
+fn test_swordfish_in_columns() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+
+    // 6 only in rows {0, 4}, {4, 8} and {0, 8} of columns 1, 3 and 6
+    for (col, rows) in [(1, [0, 4]), (3, [4, 8]), (6, [0, 8])] {
+        for r in 0..9 {
+            if !rows.contains(&r) {
+                pencil_notes.remove_possibility(r, col, 6);
+            }
+        }
+    }
+
+    // no x-wing hidden in there
+    assert_eq!(pencil_notes.find_fish(2, false), None);
+
+    let fish = pencil_notes.handle_fish(3, false).expect("found the swordfish");
+
+    assert_eq!(fish.number, 6);
+    assert_eq!(fish.base, vec![Unit::Column(1), Unit::Column(3), Unit::Column(6)]);
+    assert_eq!(fish.cover, vec![Unit::Row(0), Unit::Row(4), Unit::Row(8)]);
+    assert_eq!(fish.eliminations.len(), 3 * 6);
+    assert!(!pencil_notes.has_possibility(4, 0, 6));
+    assert!(pencil_notes.has_possibility(4, 3, 6));
+}
+
+
+#[test]
+fn test_jellyfish() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    restrict_rows(&mut pencil_notes, 9, &[(0, &[0, 3]), (2, &[3, 5]), (6, &[5, 8]), (7, &[0, 8])]);
+
+    let fish = pencil_notes.handle_fish(4, false).expect("found the jellyfish");
+
+    assert_eq!(fish.number, 9);
+    assert_eq!(fish.cover, vec![Unit::Column(0), Unit::Column(3), Unit::Column(5), Unit::Column(8)]);
+    assert_eq!(fish.eliminations.len(), 4 * 5);
+}
+
+
+#[test]
+fn test_finned_x_wing() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+
+    // rows 1 and 5 hold 4 in columns 2 and 7, row 1 also in (1, 6) and (1, 8)
+    restrict_rows(&mut pencil_notes, 4, &[(1, &[2, 6, 7, 8]), (5, &[2, 7])]);
+
+    // no basic fish
+    assert_eq!(pencil_notes.find_fish(2, false), None);
+
+    let fish = pencil_notes.handle_fish(2, true).expect("found the finned x-wing");
+
+    assert_eq!(fish.number, 4);
+    assert_eq!(fish.base, vec![Unit::Row(1), Unit::Row(5)]);
+    assert_eq!(fish.cover, vec![Unit::Column(2), Unit::Column(7)]);
+    assert_eq!(fish.fins, vec![(1, 6), (1, 8)]);
+    assert!(!fish.sashimi);
+
+    // only the cells of the cover seeing the fins
+    assert_eq!(fish.eliminations, vec![(0, 7, 4), (2, 7, 4)]);
+    assert!(pencil_notes.has_possibility(3, 7, 4));
+    assert!(pencil_notes.has_possibility(0, 2, 4));
+}
+
+
+#[test]
+fn test_sashimi_x_wing() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+
+    // row 1 has a single place in the cover and a fin next to it
+    restrict_rows(&mut pencil_notes, 4, &[(1, &[7, 8]), (5, &[2, 7])]);
+
+    let fish = pencil_notes.handle_fish(2, true).expect("found the sashimi x-wing");
+
+    assert_eq!(fish.cover, vec![Unit::Column(2), Unit::Column(7)]);
+    assert_eq!(fish.fins, vec![(1, 8)]);
+    assert!(fish.sashimi);
+    assert_eq!(fish.eliminations, vec![(0, 7, 4), (2, 7, 4)]);
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use sudoku::sudoku::Sudoku;
+use sudoku::sudoku_error::SudokuError;
+use sudoku::sudoku_game::Game;
+
+use common::PUZZLE;
+
+
+fn puzzle() -> Sudoku<9, 9> {
+
+    Sudoku::new(PUZZLE)
+}
+
+
+#[test]
+fn test_givens_are_kept_apart() {
+
+    let mut game = Game::try_new(puzzle()).unwrap();
+
+    assert_eq!(game.is_given(0, 0), Ok(true));
+    assert_eq!(game.is_given(0, 2), Ok(false));
+    assert!(matches!(game.is_given(0, 9), Err(SudokuError::CellOutOfRange { .. })));
+    assert_eq!(game.enter(0, 0, 1), Err(SudokuError::GivenCell { row: 0, col: 0 }));
+    assert_eq!(game.toggle_mark(0, 1, 1), Err(SudokuError::GivenCell { row: 0, col: 1 }));
+    assert!(matches!(game.enter(0, 2, 10), Err(SudokuError::InvalidDigit { .. })));
+    assert!(matches!(game.enter(9, 2, 1), Err(SudokuError::CellOutOfRange { .. })));
+
+    // the solution has a 4 at (0, 2)
+    assert_eq!(game.enter(0, 2, 4), Ok(()));
+    assert_eq!(game.givens, puzzle());
+    assert_eq!(game.entries.board[0][2], 4);
+    assert_eq!(game.board().board[0][2], 4);
+    assert!(!game.notes.has_possibility(0, 5, 4));
+
+    assert_eq!(game.toggle_mark(0, 3, 2), Ok(()));
+    assert!(game.marks.has_possibility(0, 3, 2));
+    assert_eq!(game.marks.count_possibilities(0, 3), 1);
+    assert_eq!(game.toggle_mark(0, 3, 2), Ok(()));
+    assert!(!game.marks.has_possibility(0, 3, 2));
+
+    assert_eq!(game.erase(0, 2), Ok(()));
+    assert_eq!(game.board(), puzzle());
+    assert!(game.notes.has_possibility(0, 5, 4));
+}
+
+
+#[test]
+fn test_check_entries_and_reset() {
+
+    let mut game = Game::try_new(puzzle()).unwrap();
+    let solution = game.solution.expect("the puzzle has a solution");
+
+    game.enter(0, 2, 4).unwrap();
+    game.enter(0, 3, 2).unwrap();
+    game.toggle_mark(8, 0, 3).unwrap();
+
+    assert_eq!(game.check_entry(0, 2), Ok(Some(true)));
+    assert_eq!(game.check_entry(0, 3), Ok(Some(false)));
+    assert_eq!(game.check_entry(0, 4), Ok(None));
+    assert!(matches!(game.check_entry(9, 0), Err(SudokuError::CellOutOfRange { .. })));
+    assert_eq!(game.mistakes(), vec![(0, 3)]);
+    assert!(!game.is_solved());
+
+    game.reset();
+    assert_eq!(game.board(), puzzle());
+    assert!(game.mistakes().is_empty());
+    assert!(!game.marks.has_possibility(8, 0, 3));
+
+    for r in 0..9 {
+        for c in 0..9 {
+            if !game.is_given(r, c).unwrap() {
+                game.enter(r, c, solution.board[r][c]).unwrap();
+            }
+        }
+    }
+    assert!(game.is_solved());
+}
+
+
+#[test]
+fn test_enter_clears_marks() {
+
+    let mut game = Game::try_new(puzzle()).unwrap();
+
+    game.toggle_mark(0, 2, 1).unwrap();
+    game.toggle_mark(0, 2, 4).unwrap();
+    game.toggle_mark(0, 3, 2).unwrap();
+
+    game.enter(0, 2, 4).unwrap();
+    assert_eq!(game.marks.count_possibilities(0, 2), 0);
+    assert!(game.marks.has_possibility(0, 3, 2));
+
+    // the marks don't come back once the number is erased
+    game.erase(0, 2).unwrap();
+    assert_eq!(game.marks.count_possibilities(0, 2), 0);
+}
+
+
+#[test]
+fn test_no_solution_without_unique_solution() {
+
+    // two givens leave many solutions, so entries can't be checked
+    let mut board = [[0; 9]; 9];
+    board[0][0] = 1;
+    board[4][4] = 5;
+
+    let mut game = Game::<9, 9>::try_new(Sudoku::new(board)).unwrap();
+    assert_eq!(game.solution, None);
+
+    game.enter(0, 1, 2).unwrap();
+    assert_eq!(game.check_entry(0, 1), Ok(None));
+    assert!(game.mistakes().is_empty());
+}
+
+
+#[test]
+fn test_invalid_givens() {
+
+    let mut board = PUZZLE;
+    board[0][2] = 40;
+    assert_eq!(Game::<9, 9>::try_new(Sudoku::new(board)).err(), Some(SudokuError::InvalidEntry { row: 0, col: 2, value: 40, max_number: 9 }));
+
+    // a second 5 in the first row
+    board[0][2] = 5;
+    assert!(matches!(Game::<9, 9>::try_new(Sudoku::new(board)), Err(SudokuError::Conflict { .. })));
+}
@@ -0,0 +1,70 @@
+use sudoku::sudoku_geometry::Geometry;
+use sudoku::sudoku_iterator::{SudokuIterator, SudokuIteratorMode, Unit};
+use sudoku::sudoku_pencil_notes::PencilNotes;
+
+
+#[test]
+fn test_geometry_peers()
+{
+    let geometry = Geometry::of::<9, 9, 3, 3>();
+
+    for row in 0..9 {
+        for col in 0..9 {
+            let peers = geometry.peers(row, col);
+
+            assert_eq!(peers.len(), 20);
+            assert!(!peers.contains(&(row, col)));
+            assert!(peers.iter().all(|&(r, c)| SudokuIterator::<9, 9>::sees(row, col, r, c)));
+
+            // the affected cells are the peers plus the cell itself, top to bottom and left to right
+            let mut affected = peers.to_vec();
+            affected.push((row, col));
+            affected.sort();
+            assert_eq!(geometry.affected(row, col), affected.as_slice());
+        }
+    }
+}
+
+
+#[test]
+fn test_geometry_units()
+{
+    let geometry = Geometry::of::<9, 9, 3, 3>();
+
+    let units: Vec<Unit> = geometry.units().map(|(unit, _)| unit).collect();
+    assert_eq!(units, Unit::all::<9, 9, 3, 3>());
+
+    for (unit, cells) in geometry.units() {
+        assert_eq!(cells, geometry.unit(unit));
+        assert_eq!(cells, unit.iter::<9, 9, 3, 3>().collect::<Vec<_>>().as_slice());
+    }
+
+    assert_eq!(geometry.units_of(4, 7), [Unit::Row(4), Unit::Column(7), Unit::Square(5)]);
+    assert_eq!(geometry.unit(Unit::Square(8))[0], (6, 6));
+}
+
+
+#[test]
+fn test_geometry_is_shared()
+{
+    // every call for a grid size hands out the same table
+    assert!(std::ptr::eq(Geometry::of::<9, 9, 3, 3>(), Geometry::of::<9, 9, 3, 3>()));
+    assert!(!std::ptr::eq(Geometry::of::<9, 9, 3, 3>(), Geometry::of::<12, 12, 3, 4>()));
+    assert_eq!(Geometry::of::<12, 12, 3, 4>().peers(0, 0).len(), 11 + 11 + 6);
+}
+
+
+#[test]
+fn test_geometry_kept_by_pencil_notes()
+{
+    let pencil_notes = PencilNotes::<9, 9>::new();
+    let geometry = pencil_notes.geometry();
+
+    // looked up when the notes are made and shared by their clones
+    assert!(std::ptr::eq(geometry, Geometry::of::<9, 9, 3, 3>()));
+    assert!(std::ptr::eq(pencil_notes.clone().geometry(), geometry));
+
+    let affected: Vec<(usize, usize)> = SudokuIterator::<9, 9>::with_geometry(geometry, 4, 7, SudokuIteratorMode::Affected).collect();
+    assert_eq!(affected, SudokuIterator::<9, 9>::new(4, 7, SudokuIteratorMode::Affected).collect::<Vec<_>>());
+    assert_eq!(affected.as_slice(), geometry.affected(4, 7));
+}
@@ -0,0 +1,176 @@
+mod common;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use sudoku::sudoku::Sudoku;
+use sudoku::sudoku_factory::{SudokuFactory, Symmetry};
+use sudoku::sudoku_iterator::Unit;
+use sudoku::sudoku_logical_solver::{LogicalOutcome, LogicalSolver, Technique};
+
+use common::PUZZLE;
+
+
+// needs locked candidates and a naked pair
+const PAIRS: [[u32; 9]; 9] = [
+     [0, 2, 0, 0, 7, 0, 0, 5, 1]
+    ,[0, 0, 8, 4, 0, 0, 0, 7, 0]
+    ,[0, 0, 0, 0, 0, 0, 6, 0, 8]
+    ,[6, 7, 0, 0, 5, 0, 0, 0, 0]
+    ,[0, 0, 2, 0, 0, 0, 0, 0, 0]
+    ,[0, 0, 0, 6, 0, 0, 0, 9, 0]
+    ,[0, 4, 0, 0, 0, 2, 8, 0, 0]
+    ,[0, 0, 0, 0, 0, 0, 3, 1, 4]
+    ,[0, 8, 0, 0, 1, 0, 5, 0, 0]];
+
+// "Platinum Blonde", far beyond singles and pairs
+const HARD: [[u32; 9]; 9] = [
+     [0, 0, 0, 0, 0, 0, 0, 1, 2]
+    ,[0, 0, 0, 0, 0, 0, 0, 0, 3]
+    ,[0, 0, 2, 3, 0, 0, 4, 0, 0]
+    ,[0, 0, 1, 8, 0, 0, 0, 0, 5]
+    ,[0, 6, 0, 0, 7, 0, 8, 0, 0]
+    ,[0, 0, 0, 0, 0, 9, 0, 0, 0]
+    ,[0, 0, 8, 5, 0, 0, 0, 0, 0]
+    ,[9, 0, 0, 0, 4, 0, 5, 0, 0]
+    ,[4, 7, 0, 0, 0, 6, 0, 0, 0]];
+
+
+#[test]
+fn test_logical_solver_singles()
+{
+    let sudoku = Sudoku::<9,9>::new(PUZZLE);
+
+    let solution = LogicalSolver::new(&sudoku).solve();
+
+    assert_eq!(solution.outcome, LogicalOutcome::Solved);
+    assert_eq!(Some(solution.sudoku), sudoku.solve());
+
+    // one step per empty cell, all of them singles
+    let empty = PUZZLE.iter().flatten().filter(|&&n| n == 0).count();
+    assert_eq!(solution.steps.len(), empty);
+    assert!(solution.steps.iter().all(|step| step.technique <= Technique::HiddenSingle));
+
+    // replaying the placements gives the solution
+    let mut replay = sudoku;
+    for step in &solution.steps {
+        let (r, c, number) = step.placement.expect("singles place a number");
+        assert_eq!(replay.board[r][c], 0);
+        replay.board[r][c] = number;
+    }
+    assert_eq!(replay, solution.sudoku);
+}
+
+
+#[test]
+fn test_logical_solver_pairs()
+{
+    let sudoku = Sudoku::<9,9>::new(PAIRS);
+
+    let solution = LogicalSolver::new(&sudoku).solve();
+
+    assert_eq!(solution.outcome, LogicalOutcome::Solved);
+    assert_eq!(Some(solution.sudoku), sudoku.solve());
+
+    let elimination_steps: Vec<_> = solution.steps.iter().filter(|step| step.placement.is_none()).collect();
+
+    assert_eq!(elimination_steps[0].technique, Technique::Pointing);
+    assert_eq!(elimination_steps[0].unit, Some(Unit::Square(2)));
+    assert_eq!(elimination_steps[0].eliminations, vec![(1, 4, 2)]);
+
+    assert_eq!(elimination_steps[3].technique, Technique::Claiming);
+    assert_eq!(elimination_steps[3].unit, Some(Unit::Row(0)));
+    assert_eq!(elimination_steps[3].eliminations, vec![(1, 0, 3), (2, 0, 3), (2, 1, 3), (2, 2, 3)]);
+
+    assert_eq!(elimination_steps[5].technique, Technique::NakedPair);
+    assert_eq!(elimination_steps[5].unit, Some(Unit::Column(4)));
+
+    // no step ever eliminates the number of the solution
+    for step in &solution.steps {
+        for &(r, c, number) in &step.eliminations {
+            assert_ne!(solution.sudoku.board[r][c], number, "{:?} eliminated the solution", step);
+        }
+    }
+}
+
+
+#[test]
+fn test_logical_solver_step_by_step()
+{
+    let sudoku = Sudoku::<9,9>::new(PUZZLE);
+    let mut solver = LogicalSolver::new(&sudoku);
+
+    let step = solver.step().expect("easy puzzle has a single");
+    let (r, c, number) = step.placement.unwrap();
+
+    assert_eq!(solver.sudoku().board[r][c], number);
+    assert_eq!(solver.pencil_notes().get_possibility(r, c), Some(number));
+}
+
+
+#[test]
+fn test_logical_solver_stuck()
+{
+    let sudoku = Sudoku::<9,9>::new(HARD);
+    let expected = sudoku.solve().unwrap();
+
+    let solution = LogicalSolver::new(&sudoku).solve();
+
+    assert_eq!(solution.outcome, LogicalOutcome::Stuck);
+    assert!(!solution.sudoku.is_complete());
+
+    // whatever was placed is correct, nothing was guessed
+    for r in 0..9 {
+        for c in 0..9 {
+            if solution.sudoku.board[r][c] != 0 {
+                assert_eq!(solution.sudoku.board[r][c], expected.board[r][c]);
+            }
+        }
+    }
+}
+
+
+#[test]
+fn test_logical_solver_contradiction()
+{
+    let mut board = PUZZLE;
+    board[0][2] = 5;
+
+    let solution = LogicalSolver::new(&Sudoku::<9,9>::new(board)).solve();
+
+    assert_eq!(solution.outcome, LogicalOutcome::Contradiction);
+    assert!(solution.steps.is_empty());
+}
+
+
+#[test]
+fn test_logical_solver_is_sound()
+{
+    let mut factory = SudokuFactory::<9, 9>::new(Box::new(StdRng::seed_from_u64(3)));
+
+    for _ in 0..20 {
+
+        let puzzle = factory.generate_puzzle(0, Symmetry::None);
+
+        // generated puzzles are unique, so the uniqueness techniques have to be sound as well
+        for assume_uniqueness in [false, true] {
+
+            let mut solver = LogicalSolver::new(&puzzle.puzzle);
+            solver.assume_uniqueness = assume_uniqueness;
+            let solution = solver.solve();
+
+            assert_ne!(solution.outcome, LogicalOutcome::Contradiction);
+
+            for step in &solution.steps {
+
+                if let Some((r, c, number)) = step.placement {
+                    assert_eq!(puzzle.solution.board[r][c], number, "{:?} placed a wrong number", step);
+                }
+
+                for &(r, c, number) in &step.eliminations {
+                    assert_ne!(puzzle.solution.board[r][c], number, "{:?} eliminated the solution", step);
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 
+use sudoku::sudoku_candidates::Candidates;
 use sudoku::sudoku_pencil_notes::{PencilNotes, RandomBit};
 
 #[test]
@@ -11,13 +12,13 @@ fn test_naked_pair_removes_candidates() {
     pencil_notes.clear();
 
     // Set up two cells with identical candidate pairs
-    pencil_notes.set_possibility(0, 0, 0b000000011); // Candidates 1 and 2
-    pencil_notes.set_possibility(0, 1, 0b000000011); // Candidates 1 and 2 (this is the naked pair)
+    pencil_notes.possibilities[0][0] = 0b000000011; // Candidates 1 and 2
+    pencil_notes.possibilities[0][1] = 0b000000011; // Candidates 1 and 2 (this is the naked pair)
 
     // Set up a third cell in the same unit with additional candidates
-    pencil_notes.set_possibility(0, 2, 0b000011100); // Candidates    3, 4, 5
-    pencil_notes.set_possibility(0, 3, 0b111000001); // Naked pair removal would eliminate 1 here
-    pencil_notes.set_possibility(0, 5, 0b000101100); // Candidates    3, 4,  , 6 (this is a hidden pair)
+    pencil_notes.possibilities[0][2] = 0b000011100; // Candidates    3, 4, 5
+    pencil_notes.possibilities[0][3] = 0b111000001; // Naked pair removal would eliminate 1 here
+    pencil_notes.possibilities[0][5] = 0b000101100; // Candidates    3, 4,  , 6 (this is a hidden pair)
 
 
 /* 
@@ -25,7 +26,7 @@ fn test_naked_pair_removes_candidates() {
     for i in 0..9 {
         print!("{:09b}, ", pencil_notes.possibilities[0][i]);
     }
-    print!("\n");
+    println!();
  */
     // Apply naked pair logic
     pencil_notes.handle_naked_pairs(0, 0, sudoku::sudoku_iterator::SudokuIteratorMode::Column);
@@ -35,15 +36,15 @@ fn test_naked_pair_removes_candidates() {
     for i in 0..9 {
         print!("{:09b}, ", pencil_notes.possibilities[0][i]);
     }
-    print!("\n");
+    println!();
 */
 
     // Verify that candidates 1 and 2 have been removed from other cells in the unit
-    assert_eq!(pencil_notes.get_possibilities(0, 0), 0b000000011);
-    assert_eq!(pencil_notes.get_possibilities(0, 1), 0b000000011);
-    assert_eq!(pencil_notes.get_possibilities(0, 2), 0b000011100);
-    assert_eq!(pencil_notes.get_possibilities(0, 3), 0b111000000); // 1 and 2 removed
-    assert_eq!(pencil_notes.get_possibilities(0, 5), 0b000101100);
+    assert_eq!(pencil_notes.get_possibilities(0, 0), Candidates::from_numbers(&[1, 2]));
+    assert_eq!(pencil_notes.get_possibilities(0, 1), Candidates::from_numbers(&[1, 2]));
+    assert_eq!(pencil_notes.get_possibilities(0, 2), Candidates::from_numbers(&[3, 4, 5]));
+    assert_eq!(pencil_notes.get_possibilities(0, 3), Candidates::from_numbers(&[7, 8, 9])); // 1 and 2 removed
+    assert_eq!(pencil_notes.get_possibilities(0, 5), Candidates::from_numbers(&[3, 4, 6]));
 
 }
 
@@ -57,13 +58,13 @@ fn test_hidden_pair_removes_candidates() {
     pencil_notes.clear();
 
     // Set up two cells with identical candidate pairs
-    pencil_notes.set_possibility(0, 0, 0b000000011); // Candidates 1 and 2
-    pencil_notes.set_possibility(0, 1, 0b000000011); // Candidates 1 and 2 (this is the naked pair)
+    pencil_notes.possibilities[0][0] = 0b000000011; // Candidates 1 and 2
+    pencil_notes.possibilities[0][1] = 0b000000011; // Candidates 1 and 2 (this is the naked pair)
 
     // Set up a third cell in the same unit with additional candidates
-    pencil_notes.set_possibility(0, 2, 0b000011100); // Candidates    3, 4, 5    (3, 4 is hidden pair)
-    pencil_notes.set_possibility(0, 3, 0b111000001); // naked pair removal would eliminate 1 here
-    pencil_notes.set_possibility(0, 5, 0b000101100); // Candidates    3, 4,  , 6 (3, 4 is a hidden pair)
+    pencil_notes.possibilities[0][2] = 0b000011100; // Candidates    3, 4, 5    (3, 4 is hidden pair)
+    pencil_notes.possibilities[0][3] = 0b111000001; // naked pair removal would eliminate 1 here
+    pencil_notes.possibilities[0][5] = 0b000101100; // Candidates    3, 4,  , 6 (3, 4 is a hidden pair)
 
 
 
@@ -71,7 +72,7 @@ fn test_hidden_pair_removes_candidates() {
     for i in 0..9 {
         print!("{:09b}, ", pencil_notes.possibilities[0][i]);
     }
-    print!("\n");
+    println!();
 
     // Apply naked pair logic
     pencil_notes.handle_hidden_pairs(0, 0, sudoku::sudoku_iterator::SudokuIteratorMode::Column);
@@ -81,15 +82,15 @@ fn test_hidden_pair_removes_candidates() {
     for i in 0..9 {
         print!("{:09b}, ", pencil_notes.possibilities[0][i]);
     }
-    print!("\n");
+    println!();
 
 
     // Verify that candidates 1 and 2 have been removed from other cells in the unit
-    assert_eq!(pencil_notes.get_possibilities(0, 0), 0b000000011);
-    assert_eq!(pencil_notes.get_possibilities(0, 1), 0b000000011);
-    assert_eq!(pencil_notes.get_possibilities(0, 2), 0b000001100);
-    assert_eq!(pencil_notes.get_possibilities(0, 3), 0b111000001); // 1 and 2 removed
-    assert_eq!(pencil_notes.get_possibilities(0, 5), 0b000001100);
+    assert_eq!(pencil_notes.get_possibilities(0, 0), Candidates::from_numbers(&[1, 2]));
+    assert_eq!(pencil_notes.get_possibilities(0, 1), Candidates::from_numbers(&[1, 2]));
+    assert_eq!(pencil_notes.get_possibilities(0, 2), Candidates::from_numbers(&[3, 4]));
+    assert_eq!(pencil_notes.get_possibilities(0, 3), Candidates::from_numbers(&[1, 7, 8, 9])); // 1 and 2 removed
+    assert_eq!(pencil_notes.get_possibilities(0, 5), Candidates::from_numbers(&[3, 4]));
 
 }
 
@@ -112,7 +113,7 @@ fn test_returns_exact_index_for_single_bit(){
     let mut random_bit = RandomBit::new(Box::new(StdRng::seed_from_u64(42)));
     
     // Run it multiple times to ensure it's not accidentally working
-    for i in 0..10 as u32 {
+    for i in 0..10u32 {
         let mask = 1 << i;
         assert_eq!(random_bit.select_random_bit(mask), Some(i));
     }
@@ -129,7 +130,7 @@ fn test_picks_valid_options_only() {
 
         assert!(result < 32, "Function returned out-of-bounds index: {}", result);
 
-        let result = (1 as u32) << result; // Convert to 0-based index
+        let result = 1u32 << result; // Convert to 0-based index
 
         let valid = mask & result == result;
         assert!(valid, "Function selected an invalid bit: {:08x} != {:08x}", result, mask);
@@ -179,11 +180,130 @@ fn test_select_random()
 
             let old_mask = mask;
             let selected = 1u32 << number;
-            mask = mask & !selected;
+            mask &= !selected;
             println!("selected from mask: 0b{:09b} & !0b{:09b} ({}) -> 0b{:09b}", old_mask, selected, number, mask);
 
         } else {
             println!("Could not select from mask 0b{:09b}", mask);
         }
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_pointing_removes_candidates() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+
+    // 5 can only go into (0, 0) and (0, 1) within the top left square
+    for (r, c) in [(0, 2), (1, 0), (1, 1), (1, 2), (2, 0), (2, 1), (2, 2)] {
+        pencil_notes.remove_possibility(r, c, 5);
+    }
+
+    let eliminated = pencil_notes.handle_pointing(1, 1);
+
+    assert_eq!(eliminated, vec![(0, 3, 5), (0, 4, 5), (0, 5, 5), (0, 6, 5), (0, 7, 5), (0, 8, 5)]);
+    assert!(pencil_notes.has_possibility(0, 0, 5));
+    assert!(pencil_notes.has_possibility(0, 1, 5));
+    assert!(pencil_notes.has_possibility(3, 0, 5));
+
+    // nothing left to do
+    assert!(pencil_notes.handle_pointing(0, 0).is_empty());
+}
+
+
+#[test]
+fn test_claiming_removes_candidates() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+
+    // 7 can only go into (4, 6) and (4, 8) within row 4
+    for c in 0..6 {
+        pencil_notes.remove_possibility(4, c, 7);
+    }
+    pencil_notes.remove_possibility(4, 7, 7);
+
+    let eliminated = pencil_notes.handle_claiming(4, 0, sudoku::sudoku_iterator::SudokuIteratorMode::Column);
+
+    assert_eq!(eliminated, vec![(3, 6, 7), (3, 7, 7), (3, 8, 7), (5, 6, 7), (5, 7, 7), (5, 8, 7)]);
+    assert!(pencil_notes.has_possibility(4, 6, 7));
+    assert!(pencil_notes.has_possibility(2, 6, 7));
+
+    // the same in a column: 3 only in (0, 2) and (1, 2)
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    for r in 2..9 {
+        pencil_notes.remove_possibility(r, 2, 3);
+    }
+
+    let eliminated = pencil_notes.handle_claiming(0, 2, sudoku::sudoku_iterator::SudokuIteratorMode::Row);
+
+    assert_eq!(eliminated, vec![(0, 0, 3), (0, 1, 3), (1, 0, 3), (1, 1, 3), (2, 0, 3), (2, 1, 3)]);
+}
+
+
+#[test]
+fn test_naked_triple_removes_candidates() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+
+    // {1, 2}, {2, 3} and {1, 3} hold only 1, 2 and 3 between them
+    pencil_notes.possibilities[0][0] = 0b000000011;
+    pencil_notes.possibilities[0][4] = 0b000000110;
+    pencil_notes.possibilities[0][8] = 0b000000101;
+
+    let eliminated = pencil_notes.handle_naked_subsets(0, 0, sudoku::sudoku_iterator::SudokuIteratorMode::Column, 3);
+
+    assert_eq!(eliminated.len(), 6 * 3);
+    for c in [1, 2, 3, 5, 6, 7] {
+        assert_eq!(pencil_notes.get_possibilities(0, c), Candidates::from_numbers(&[4, 5, 6, 7, 8, 9]));
+    }
+    assert_eq!(pencil_notes.get_possibilities(0, 0), Candidates::from_numbers(&[1, 2]));
+
+    // a triple is no pair
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    pencil_notes.possibilities[0][0] = 0b000000011;
+    pencil_notes.possibilities[0][4] = 0b000000110;
+    pencil_notes.possibilities[0][8] = 0b000000101;
+
+    assert!(pencil_notes.handle_naked_subsets(0, 0, sudoku::sudoku_iterator::SudokuIteratorMode::Column, 2).is_empty());
+}
+
+
+#[test]
+fn test_hidden_triple_removes_candidates() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+
+    // 4, 5 and 6 can only go into rows 1, 3 and 7 of column 2
+    for r in [0, 2, 4, 5, 6, 8] {
+        for number in [4, 5, 6] {
+            pencil_notes.remove_possibility(r, 2, number);
+        }
+    }
+
+    let eliminated = pencil_notes.handle_hidden_subsets(0, 2, sudoku::sudoku_iterator::SudokuIteratorMode::Row, 3);
+
+    assert_eq!(eliminated.len(), 3 * 6);
+    for r in [1, 3, 7] {
+        assert_eq!(pencil_notes.get_possibilities(r, 2), Candidates::from_numbers(&[4, 5, 6]));
+    }
+    assert_eq!(pencil_notes.get_possibilities(0, 2), Candidates::from_numbers(&[1, 2, 3, 7, 8, 9]));
+}
+
+
+#[test]
+fn test_naked_quad_removes_candidates() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+
+    // four cells of the center square sharing 2, 4, 6 and 8
+    pencil_notes.possibilities[3][3] = 0b010001010;
+    pencil_notes.possibilities[3][5] = 0b000101000;
+    pencil_notes.possibilities[4][4] = 0b010100000;
+    pencil_notes.possibilities[5][3] = 0b000001010;
+
+    let eliminated = pencil_notes.handle_naked_subsets(4, 4, sudoku::sudoku_iterator::SudokuIteratorMode::Square, 4);
+
+    assert_eq!(eliminated.len(), 5 * 4);
+    assert_eq!(pencil_notes.get_possibilities(3, 4), Candidates::from_numbers(&[1, 3, 5, 7, 9]));
+    assert_eq!(pencil_notes.get_possibilities(3, 3), Candidates::from_numbers(&[2, 4, 8]));
+}
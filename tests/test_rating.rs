@@ -0,0 +1,90 @@
+mod common;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use sudoku::sudoku::Sudoku;
+use sudoku::sudoku_factory::{SudokuFactory, Symmetry};
+use sudoku::sudoku_logical_solver::{LogicalSolver, Technique};
+use sudoku::sudoku_rating::{rate, technique_score, Difficulty};
+
+use common::PUZZLE;
+
+
+#[test]
+fn test_rate_easy()
+{
+    let sudoku = Sudoku::<9,9>::new(PUZZLE);
+
+    let rating = rate(&sudoku);
+
+    assert!(rating.solved);
+    assert_eq!(rating.difficulty, Difficulty::Easy);
+    assert!(rating.hardest <= Some(Technique::HiddenSingle));
+    assert!(rating.score > 0);
+}
+
+
+#[test]
+fn test_rate_medium()
+{
+    let sudoku = Sudoku::<9,9>::new([
+         [0, 2, 0, 0, 7, 0, 0, 5, 1]
+        ,[0, 0, 8, 4, 0, 0, 0, 7, 0]
+        ,[0, 0, 0, 0, 0, 0, 6, 0, 8]
+        ,[6, 7, 0, 0, 5, 0, 0, 0, 0]
+        ,[0, 0, 2, 0, 0, 0, 0, 0, 0]
+        ,[0, 0, 0, 6, 0, 0, 0, 9, 0]
+        ,[0, 4, 0, 0, 0, 2, 8, 0, 0]
+        ,[0, 0, 0, 0, 0, 0, 3, 1, 4]
+        ,[0, 8, 0, 0, 1, 0, 5, 0, 0]]);
+
+    let rating = rate(&sudoku);
+
+    assert!(rating.solved);
+    assert_eq!(rating.difficulty, Difficulty::Medium);
+    assert_eq!(rating.hardest, Some(Technique::NakedPair));
+}
+
+
+#[test]
+fn test_rate_needs_guessing()
+{
+    let sudoku = Sudoku::<9,9>::new([
+         [0, 0, 0, 0, 0, 0, 0, 1, 2]
+        ,[0, 0, 0, 0, 0, 0, 0, 0, 3]
+        ,[0, 0, 2, 3, 0, 0, 4, 0, 0]
+        ,[0, 0, 1, 8, 0, 0, 0, 0, 5]
+        ,[0, 6, 0, 0, 7, 0, 8, 0, 0]
+        ,[0, 0, 0, 0, 0, 9, 0, 0, 0]
+        ,[0, 0, 8, 5, 0, 0, 0, 0, 0]
+        ,[9, 0, 0, 0, 4, 0, 5, 0, 0]
+        ,[4, 7, 0, 0, 0, 6, 0, 0, 0]]);
+
+    let rating = rate(&sudoku);
+
+    assert!(!rating.solved);
+    assert_eq!(rating.difficulty, Difficulty::Extreme);
+
+    // the hardest technique is the one with the highest score, not the last one in the enum
+    let steps = LogicalSolver::new(&sudoku).solve().steps;
+    let highest = steps.iter().map(|step| technique_score(step.technique)).max();
+    assert_eq!(rating.hardest.map(technique_score), highest);
+}
+
+
+#[test]
+fn test_generate_with_difficulty()
+{
+    let mut factory = SudokuFactory::<9, 9>::new(Box::new(StdRng::seed_from_u64(42)));
+
+    for difficulty in [Difficulty::Easy, Difficulty::Expert] {
+
+        let (puzzle, rating) = factory.generate_puzzle_with_difficulty(difficulty, Symmetry::None, 50)
+            .expect("found a puzzle of the requested difficulty");
+
+        assert_eq!(rating.difficulty, difficulty);
+        assert_eq!(rate(&puzzle.puzzle), rating);
+        assert!(puzzle.puzzle.has_unique_solution());
+    }
+}
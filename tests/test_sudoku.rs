@@ -1,21 +1,16 @@
-use sudoku::sudoku::Sudoku;
+mod common;
+
+use sudoku::sudoku::{Conflict, Sudoku};
+use sudoku::sudoku_error::SudokuError;
+use sudoku::sudoku_iterator::Unit;
+
+use common::PUZZLE;
 
 
 #[test]
 fn test_sudoku_validation_valid()
 {
-    let board: [[u32; 9]; 9] = [
-         [5, 3, 0, 0, 7, 0, 0, 0, 0]
-        ,[6, 0, 0, 1, 9, 5, 0, 0, 0]
-        ,[0, 9, 8, 0, 0, 0, 0, 6, 0]
-        ,[8, 0, 0, 0, 6, 0, 0, 0, 3]
-        ,[4, 0, 0, 8, 0, 3, 0, 0, 1]
-        ,[7, 0, 0, 0, 2, 0, 0, 0, 6]
-        ,[0, 6, 0, 0, 0, 0, 2, 8, 0]
-        ,[0, 0, 0, 4, 1, 9, 0, 0, 5]
-        ,[0, 0, 0, 0, 8, 0, 0, 7, 9]];
-
-    let sudoku = Sudoku::<9,9>::new(board);
+    let sudoku = Sudoku::<9,9>::new(PUZZLE);
     assert!(sudoku.is_valid());
 }
 
@@ -39,3 +34,30 @@ fn test_sudoku_validation_not_valid()
     assert!(!sudoku.is_valid());
 }
 
+
+#[test]
+fn test_sudoku_conflicts()
+{
+    let mut board = [[0; 9]; 9];
+    board[0][0] = 8;
+    board[0][7] = 8;
+    board[1][1] = 8;
+    board[5][4] = 2;
+    board[8][4] = 2;
+
+    let sudoku = Sudoku::<9,9>::new(board);
+    let conflicts = sudoku.conflicts();
+
+    assert_eq!(conflicts, vec![
+        Conflict { unit: Unit::Row(0), number: 8, cells: vec![(0, 0), (0, 7)] },
+        Conflict { unit: Unit::Column(4), number: 2, cells: vec![(5, 4), (8, 4)] },
+        Conflict { unit: Unit::Square(0), number: 8, cells: vec![(0, 0), (1, 1)] },
+    ]);
+
+    assert!(!sudoku.is_valid());
+    assert_eq!(sudoku.validate(), Err(SudokuError::Conflict { unit: Unit::Row(0), number: 8, cells: vec![(0, 0), (0, 7)] }));
+    assert_eq!(sudoku.validate().unwrap_err().to_string(), "8 shows up more than once in Row(0), in cells (0, 0), (0, 7)");
+
+    assert!(Sudoku::<9,9>::new([[0; 9]; 9]).conflicts().is_empty());
+    assert_eq!(Sudoku::<9,9>::new([[0; 9]; 9]).validate(), Ok(()));
+}
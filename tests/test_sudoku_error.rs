@@ -0,0 +1,85 @@
+use sudoku::sudoku::Sudoku;
+use sudoku::sudoku_cell::{CellPos, Digit};
+use sudoku::sudoku_error::SudokuError;
+use sudoku::sudoku_pencil_notes::PencilNotes;
+
+
+#[test]
+fn test_digit_and_cell_pos() {
+
+    assert_eq!(Digit::<9, 9, 3, 3>::new(9).map(Digit::get), Ok(9));
+    assert_eq!(Digit::<9, 9, 3, 3>::new(0), Err(SudokuError::InvalidDigit { value: 0, max_number: 9 }));
+    assert_eq!(Digit::<6, 6, 2, 3>::new(7), Err(SudokuError::InvalidDigit { value: 7, max_number: 6 }));
+
+    let pos = CellPos::<9, 9, 3, 3>::new(8, 0).unwrap();
+    assert_eq!((pos.row(), pos.col()), (8, 0));
+    assert_eq!(CellPos::<9, 9, 3, 3>::new(2, 9), Err(SudokuError::CellOutOfRange { row: 2, col: 9, n_rows: 9, n_cols: 9 }));
+}
+
+
+#[test]
+fn test_try_new_and_try_set() {
+
+    let mut board = [[0; 9]; 9];
+    board[4][5] = 10;
+    assert_eq!(Sudoku::<9, 9>::try_new(board), Err(SudokuError::InvalidEntry { row: 4, col: 5, value: 10, max_number: 9 }));
+
+    board[4][5] = 9;
+    let mut sudoku = Sudoku::<9, 9>::try_new(board).unwrap();
+
+    assert_eq!(sudoku.try_set(0, 0, 3), Ok(()));
+    assert_eq!(sudoku.get(CellPos::<9, 9, 3, 3>::new(0, 0).unwrap()).map(Digit::get), Some(3));
+
+    assert_eq!(sudoku.try_set(0, 0, 0), Ok(()));
+    assert_eq!(sudoku.board[0][0], 0);
+
+    assert!(matches!(sudoku.try_set(9, 0, 1), Err(SudokuError::CellOutOfRange { .. })));
+    assert!(matches!(sudoku.try_set(0, 0, 10), Err(SudokuError::InvalidDigit { .. })));
+    assert_eq!(sudoku.board[0][0], 0);
+
+    assert_eq!(SudokuError::InvalidDigit { value: 10, max_number: 9 }.to_string(), "10 is not a number between 1 and 9");
+}
+
+
+#[test]
+fn test_pencil_notes_try_possibility() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+
+    assert_eq!(pencil_notes.try_remove_possibility(0, 0, 5), Ok(()));
+    assert!(!pencil_notes.has_possibility(0, 0, 5));
+
+    assert_eq!(pencil_notes.try_add_possibility(0, 0, 5), Ok(()));
+    assert!(pencil_notes.has_possibility(0, 0, 5));
+
+    assert!(matches!(pencil_notes.try_add_possibility(0, 0, 0), Err(SudokuError::InvalidDigit { .. })));
+    assert!(matches!(pencil_notes.try_remove_possibility(0, 12, 1), Err(SudokuError::CellOutOfRange { .. })));
+    assert_eq!(pencil_notes, PencilNotes::new());
+}
+
+
+#[test]
+fn test_pencil_notes_outside_the_grid() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+
+    assert_eq!(pencil_notes.try_set_possibility(4, 4, 7), Ok(()));
+    assert_eq!(pencil_notes.count_possibilities(4, 4), 1);
+    assert!(matches!(pencil_notes.try_set_possibility(4, 4, 10), Err(SudokuError::InvalidDigit { .. })));
+
+    // reading or removing outside the grid neither panics nor changes anything
+    assert!(!pencil_notes.has_possibility(0, 0, 0));
+    assert!(!pencil_notes.has_possibility(0, 0, 10));
+    assert!(!pencil_notes.has_possibility(9, 0, 1));
+    pencil_notes.remove_possibility(0, 9, 1);
+    pencil_notes.remove_possibility(0, 0, 0);
+    assert_eq!(pencil_notes.count_possibilities(0, 0), 9);
+
+    let mut board = [[0; 9]; 9];
+    board[2][3] = 40;
+    let sudoku = Sudoku::<9, 9>::new(board);
+
+    assert_eq!(PencilNotes::<9, 9>::try_from_sudoku(&sudoku), Err(SudokuError::InvalidEntry { row: 2, col: 3, value: 40, max_number: 9 }));
+    // unchecked, the cell is left without candidates
+    assert_eq!(PencilNotes::<9, 9>::from_sudoku(&sudoku).count_possibilities(2, 3), 0);
+}
@@ -1,9 +1,15 @@
-use sudoku::sudoku_factory::{self, SudokuFactory}; // Import the function from the parent module
-use sudoku::sudoku_fmt::*;
+use sudoku::sudoku::Sudoku;
+use sudoku::sudoku_factory::{SudokuFactory, Symmetry};
+use sudoku::sudoku_iterator::SudokuIteratorMode;
+use sudoku::sudoku_observer::{GenerationObserver, StdoutObserver};
+use sudoku::sudoku_pencil_notes::PencilNotes;
 
 use rand::SeedableRng;
 use rand::rngs::StdRng;
 
+use std::cell::RefCell;
+use std::rc::Rc;
+
 
 #[test]
 fn test_sudoku_generation() {
@@ -30,3 +36,124 @@ fn test_sudoku_generation() {
         assert!(sudoku.is_valid(), "Generated Sudoku is not valid!");
     }
 }
+
+
+#[test]
+fn test_puzzle_generation() {
+
+    const N_ROWS: usize = 9;
+    const N_COLS: usize = 9;
+
+    let rng = Box::new(StdRng::seed_from_u64(42));
+
+    let mut factory = SudokuFactory::<N_ROWS, N_COLS>::new(rng);
+    for n in 0..5 {
+        let puzzle = factory.generate_puzzle(30, Symmetry::None);
+
+        println!("Generated Puzzle: {} ({} clues)\n{}", n, puzzle.clues(), puzzle.puzzle);
+
+        assert!(puzzle.clues() >= 30, "Removed too many givens!");
+        assert!(puzzle.puzzle.has_unique_solution(), "Generated puzzle is ambiguous!");
+        assert_eq!(puzzle.puzzle.solve(), Some(puzzle.solution));
+
+        assert!(puzzle.solution.is_complete());
+        assert!(puzzle.solution.is_valid());
+    }
+}
+
+
+#[test]
+fn test_puzzle_generation_rotational_symmetry() {
+
+    const N_ROWS: usize = 9;
+    const N_COLS: usize = 9;
+
+    let rng = Box::new(StdRng::seed_from_u64(7));
+
+    let mut factory = SudokuFactory::<N_ROWS, N_COLS>::new(rng);
+    for _ in 0..5 {
+        let puzzle = factory.generate_puzzle(26, Symmetry::Rotational);
+
+        assert!(puzzle.clues() >= 26, "Removed too many givens!");
+        assert!(puzzle.puzzle.has_unique_solution(), "Generated puzzle is ambiguous!");
+
+        for r in 0..N_ROWS {
+            for c in 0..N_COLS {
+                let given = puzzle.puzzle.board[r][c] != 0;
+                let partner = puzzle.puzzle.board[N_ROWS - 1 - r][N_COLS - 1 - c] != 0;
+                assert_eq!(given, partner, "Givens ({}, {}) and its partner differ!", r, c);
+            }
+        }
+    }
+}
+
+
+#[derive(Default)]
+struct Counts {
+    placements: usize,
+    guesses: usize,
+    backtracks: usize,
+    snapshots: usize,
+}
+
+struct CountingObserver {
+    counts: Rc<RefCell<Counts>>,
+}
+
+impl GenerationObserver<9, 9> for CountingObserver {
+
+    fn naked_single(&mut self, _iteration: usize, _row: usize, _col: usize, _number: u32) {
+        self.counts.borrow_mut().placements += 1;
+    }
+
+    fn hidden_single(&mut self, _iteration: usize, _row: usize, _col: usize, _number: u32, _mode: SudokuIteratorMode) {
+        self.counts.borrow_mut().placements += 1;
+    }
+
+    fn random_guess(&mut self, _iteration: usize, _row: usize, _col: usize, _number: u32) {
+        let mut counts = self.counts.borrow_mut();
+        counts.placements += 1;
+        counts.guesses += 1;
+    }
+
+    fn backtrack(&mut self, _iteration: usize, _sudoku: &Sudoku<9, 9>) {
+        self.counts.borrow_mut().backtracks += 1;
+    }
+
+    fn snapshot(&mut self, iteration: usize, _sudoku: &Sudoku<9, 9>, _pencil_notes: &PencilNotes<9, 9>) {
+        let mut counts = self.counts.borrow_mut();
+        counts.snapshots += 1;
+        assert_eq!(counts.snapshots, iteration);
+    }
+}
+
+
+#[test]
+fn test_generation_observer() {
+
+    let counts = Rc::new(RefCell::new(Counts::default()));
+    let observer = Box::new(CountingObserver { counts: counts.clone() });
+
+    let mut factory = SudokuFactory::<9, 9>::with_observer(Box::new(StdRng::seed_from_u64(42)), observer);
+    let sudoku = factory.generate();
+
+    assert!(sudoku.is_complete());
+
+    let counts = counts.borrow();
+    assert!(counts.snapshots > 0);
+    assert!(counts.guesses > 0);
+    // every cell is filled once, plus again for every cell lost by backtracking
+    assert!(counts.placements >= 81);
+    assert!(counts.backtracks > 0 || counts.placements == 81);
+}
+
+
+#[test]
+fn test_generation_stdout_observer() {
+
+    // same seed, same grid, whether or not the steps are printed
+    let mut silent = SudokuFactory::<9, 9>::new(Box::new(StdRng::seed_from_u64(42)));
+    let mut printing = SudokuFactory::<9, 9>::with_observer(Box::new(StdRng::seed_from_u64(42)), Box::new(StdoutObserver));
+
+    assert_eq!(silent.generate(), printing.generate());
+}
@@ -93,4 +93,23 @@ fn test_sudoku_iterator_all_mode() {
     }
 
     assert_eq!(results, expected);
-}
\ No newline at end of file
+}
+#[test]
+fn test_units() {
+    let units = Unit::all::<9, 9, 3, 3>();
+
+    assert_eq!(units.len(), 27);
+
+    for unit in units {
+        let (row, col, mode) = unit.to_mode::<9, 9, 3, 3>();
+        assert_eq!(Unit::from_mode::<9, 9, 3, 3>(row, col, mode), Some(unit));
+        assert_eq!(unit.iter::<9, 9, 3, 3>().count(), 9);
+    }
+
+    let square: Vec<_> = Unit::Square(5).iter::<9, 9, 3, 3>().collect();
+    let expected: Vec<_> = SudokuIterator::<9, 9>::new(4, 7, SudokuIteratorMode::Square).collect();
+    assert_eq!(square, expected);
+
+    let row: Vec<_> = Unit::Row(2).iter::<9, 9, 3, 3>().collect();
+    assert_eq!(row, (0..9).map(|c| (2, c)).collect::<Vec<_>>());
+}
@@ -0,0 +1,231 @@
+mod common;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use sudoku::sudoku::Sudoku;
+use sudoku::sudoku_bitboard::BitboardSolver;
+use sudoku::sudoku_dlx::DlxSolver;
+use sudoku::sudoku_factory::{SudokuFactory, Symmetry};
+use sudoku::sudoku_logical_solver::{LogicalOutcome, LogicalSolver};
+use sudoku::sudoku_solver::{BacktrackingSolver, SudokuSolver};
+
+use common::PUZZLE;
+
+
+const SOLUTION: [[u32; 9]; 9] = [
+     [5, 3, 4, 6, 7, 8, 9, 1, 2]
+    ,[6, 7, 2, 1, 9, 5, 3, 4, 8]
+    ,[1, 9, 8, 3, 4, 2, 5, 6, 7]
+    ,[8, 5, 9, 7, 6, 1, 4, 2, 3]
+    ,[4, 2, 6, 8, 5, 3, 7, 9, 1]
+    ,[7, 1, 3, 9, 2, 4, 8, 5, 6]
+    ,[9, 6, 1, 5, 3, 7, 2, 8, 4]
+    ,[2, 8, 7, 4, 1, 9, 6, 3, 5]
+    ,[3, 4, 5, 2, 8, 6, 1, 7, 9]];
+
+
+#[test]
+fn test_solve_classic_puzzle()
+{
+    let sudoku = Sudoku::<9,9>::new(PUZZLE);
+
+    let solution = sudoku.solve().expect("puzzle has a solution");
+
+    assert_eq!(solution.board, SOLUTION);
+}
+
+
+#[test]
+fn test_solve_needs_backtracking()
+{
+    // "Platinum Blonde", not solvable with singles alone
+    let sudoku = Sudoku::<9,9>::new([
+         [0, 0, 0, 0, 0, 0, 0, 1, 2]
+        ,[0, 0, 0, 0, 0, 0, 0, 0, 3]
+        ,[0, 0, 2, 3, 0, 0, 4, 0, 0]
+        ,[0, 0, 1, 8, 0, 0, 0, 0, 5]
+        ,[0, 6, 0, 0, 7, 0, 8, 0, 0]
+        ,[0, 0, 0, 0, 0, 9, 0, 0, 0]
+        ,[0, 0, 8, 5, 0, 0, 0, 0, 0]
+        ,[9, 0, 0, 0, 4, 0, 5, 0, 0]
+        ,[4, 7, 0, 0, 0, 6, 0, 0, 0]]);
+
+    let solution = sudoku.solve().expect("puzzle has a solution");
+
+    assert!(solution.is_complete());
+    assert!(solution.is_valid());
+
+    // the givens are kept
+    for r in 0..9 {
+        for c in 0..9 {
+            if sudoku.board[r][c] != 0 {
+                assert_eq!(solution.board[r][c], sudoku.board[r][c]);
+            }
+        }
+    }
+}
+
+
+#[test]
+fn test_solve_empty_board()
+{
+    let sudoku = Sudoku::<9,9>::new([[0; 9]; 9]);
+
+    let solution = sudoku.solve().expect("empty board has a solution");
+
+    assert!(solution.is_complete());
+    assert!(solution.is_valid());
+}
+
+
+#[test]
+fn test_solve_complete_board()
+{
+    let sudoku = Sudoku::<9,9>::new(SOLUTION);
+
+    assert_eq!(sudoku.solve(), Some(sudoku));
+}
+
+
+#[test]
+fn test_solve_invalid_givens()
+{
+    let mut board = PUZZLE;
+    board[0][2] = 5; // 5 is already in row 0
+
+    let sudoku = Sudoku::<9,9>::new(board);
+
+    assert_eq!(sudoku.solve(), None);
+}
+
+
+#[test]
+fn test_solve_no_solution()
+{
+    // every given is fine on its own, but no number is left for (0, 8)
+    let mut board = [[0; 9]; 9];
+    board[0] = [1, 2, 3, 4, 5, 6, 7, 8, 0];
+    board[4][8] = 9;
+
+    let sudoku = Sudoku::<9,9>::new(board);
+
+    assert!(sudoku.is_valid());
+    assert_eq!(sudoku.solve(), None);
+}
+
+
+#[test]
+fn test_count_solutions_unique()
+{
+    let sudoku = Sudoku::<9,9>::new(PUZZLE);
+
+    assert_eq!(sudoku.count_solutions(10), 1);
+    assert!(sudoku.has_unique_solution());
+    assert_eq!(sudoku.unique_solution().map(|solution| solution.board), Some(SOLUTION));
+}
+
+
+#[test]
+fn test_count_solutions_stops_at_limit()
+{
+    let sudoku = Sudoku::<9,9>::new([[0; 9]; 9]);
+
+    assert_eq!(sudoku.count_solutions(0), 0);
+    assert_eq!(sudoku.count_solutions(1), 1);
+    assert_eq!(sudoku.count_solutions(25), 25);
+    assert!(!sudoku.has_unique_solution());
+}
+
+
+#[test]
+fn test_count_solutions_ambiguous()
+{
+    // (0, 3), (0, 4), (3, 3), (3, 4) hold 6/7 and 7/6 in two boxes,
+    // with those four cells cleared both ways round are solutions
+    let mut board = SOLUTION;
+    board[0][3] = 0;
+    board[0][4] = 0;
+    board[3][3] = 0;
+    board[3][4] = 0;
+
+    let sudoku = Sudoku::<9,9>::new(board);
+
+    assert_eq!(sudoku.count_solutions(10), 2);
+    assert_eq!(sudoku.count_solutions(1), 1);
+    assert!(!sudoku.has_unique_solution());
+    assert_eq!(sudoku.unique_solution(), None);
+}
+
+
+#[test]
+fn test_count_solutions_none()
+{
+    let mut board = PUZZLE;
+    board[0][2] = 5;
+
+    let sudoku = Sudoku::<9,9>::new(board);
+
+    assert_eq!(sudoku.count_solutions(10), 0);
+    assert!(!sudoku.has_unique_solution());
+}
+
+
+#[test]
+fn test_solve_out_of_range_given()
+{
+    // 10 is not a number of a 9x9 grid, 40 does not even fit the candidate storage
+    for value in [10, 40] {
+        let mut board = PUZZLE;
+        board[0][2] = value;
+
+        let sudoku = Sudoku::<9,9>::new(board);
+
+        assert_eq!(sudoku.solve(), None);
+        assert_eq!(sudoku.count_solutions(10), 0);
+        assert_eq!(LogicalSolver::new(&sudoku).solve().outcome, LogicalOutcome::Contradiction);
+    }
+}
+
+
+#[test]
+fn test_every_solver()
+{
+    let solvers: [&dyn SudokuSolver<9, 9>; 3] = [&BacktrackingSolver, &DlxSolver, &BitboardSolver];
+
+    let sudoku = Sudoku::<9,9>::new(PUZZLE);
+    let empty = Sudoku::<9,9>::new([[0; 9]; 9]);
+
+    let mut invalid = PUZZLE;
+    invalid[0][2] = 5;
+    let invalid = Sudoku::<9,9>::new(invalid);
+
+    // without a few givens there is more than one solution
+    let mut ambiguous = PUZZLE;
+    ambiguous[0][0] = 0;
+    ambiguous[0][1] = 0;
+    ambiguous[1][0] = 0;
+    let ambiguous = Sudoku::<9,9>::new(ambiguous);
+    let ambiguous_count = ambiguous.count_solutions(100);
+
+    let mut factory = SudokuFactory::<9, 9>::new(Box::new(StdRng::seed_from_u64(5)));
+    let generated: Vec<_> = (0..10).map(|_| factory.generate_puzzle(0, Symmetry::None)).collect();
+
+    for solver in solvers {
+
+        assert_eq!(sudoku.solve_with(solver).map(|solution| solution.board), Some(SOLUTION));
+        assert_eq!(sudoku.count_solutions_with(solver, 10), 1);
+
+        assert_eq!(invalid.solve_with(solver), None);
+        assert_eq!(invalid.count_solutions_with(solver, 10), 0);
+
+        assert_eq!(empty.count_solutions_with(solver, 0), 0);
+        assert_eq!(empty.count_solutions_with(solver, 25), 25);
+        assert_eq!(ambiguous.count_solutions_with(solver, 100), ambiguous_count);
+
+        for puzzle in &generated {
+            assert_eq!(puzzle.puzzle.solve_with(solver), Some(puzzle.solution));
+            assert_eq!(puzzle.puzzle.count_solutions_with(solver, 2), 1);
+        }
+    }
+}
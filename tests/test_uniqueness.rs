@@ -0,0 +1,150 @@
+mod common;
+
+use sudoku::sudoku::Sudoku;
+use sudoku::sudoku_candidates::Candidates;
+use sudoku::sudoku_logical_solver::{LogicalOutcome, LogicalSolver, Technique};
+use sudoku::sudoku_pencil_notes::PencilNotes;
+use sudoku::sudoku_pencil_notes_uniqueness::UniquenessKind;
+
+use common::mask;
+
+
+#[test]
+fn test_unique_rectangle_1() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    pencil_notes.possibilities[0][0] = mask(&[1, 2]);
+    pencil_notes.possibilities[0][3] = mask(&[1, 2]);
+    pencil_notes.possibilities[2][0] = mask(&[1, 2]);
+
+    let uniqueness = pencil_notes.handle_uniqueness(UniquenessKind::UniqueRectangle1).expect("found the rectangle");
+
+    assert_eq!(uniqueness.cells, vec![(0, 0), (0, 3), (2, 0), (2, 3)]);
+    assert_eq!(uniqueness.numbers, vec![1, 2]);
+    assert_eq!(uniqueness.eliminations, vec![(2, 3, 1), (2, 3, 2)]);
+    assert_eq!(pencil_notes.count_possibilities(2, 3), 7);
+}
+
+
+#[test]
+fn test_unique_rectangle_2() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    pencil_notes.possibilities[0][0] = mask(&[1, 2]);
+    pencil_notes.possibilities[0][3] = mask(&[1, 2]);
+    pencil_notes.possibilities[1][0] = mask(&[1, 2, 5]);
+    pencil_notes.possibilities[1][3] = mask(&[1, 2, 5]);
+
+    assert_eq!(pencil_notes.find_uniqueness(UniquenessKind::UniqueRectangle1), None);
+
+    let uniqueness = pencil_notes.handle_uniqueness(UniquenessKind::UniqueRectangle2).expect("found the rectangle");
+
+    assert_eq!(uniqueness.eliminations.len(), 7);
+    for c in [1, 2, 4, 5, 6, 7, 8] {
+        assert!(!pencil_notes.has_possibility(1, c, 5));
+    }
+}
+
+
+#[test]
+fn test_unique_rectangle_3() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    pencil_notes.possibilities[0][0] = mask(&[1, 2]);
+    pencil_notes.possibilities[0][3] = mask(&[1, 2]);
+    pencil_notes.possibilities[1][0] = mask(&[1, 2, 5]);
+    pencil_notes.possibilities[1][3] = mask(&[1, 2, 6]);
+    pencil_notes.possibilities[1][6] = mask(&[5, 6]);
+
+    assert_eq!(pencil_notes.find_uniqueness(UniquenessKind::UniqueRectangle2), None);
+
+    let uniqueness = pencil_notes.handle_uniqueness(UniquenessKind::UniqueRectangle3).expect("found the rectangle");
+
+    assert_eq!(uniqueness.cells, vec![(0, 0), (0, 3), (1, 0), (1, 3)]);
+    assert_eq!(uniqueness.eliminations.len(), 12);
+    for c in [1, 2, 4, 5, 7, 8] {
+        assert!(!pencil_notes.has_possibility(1, c, 5));
+        assert!(!pencil_notes.has_possibility(1, c, 6));
+    }
+    assert_eq!(pencil_notes.get_possibilities(1, 6), Candidates::from_numbers(&[5, 6]));
+}
+
+
+#[test]
+fn test_unique_rectangle_4() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    pencil_notes.possibilities[0][0] = mask(&[1, 2]);
+    pencil_notes.possibilities[0][3] = mask(&[1, 2]);
+    pencil_notes.possibilities[1][0] = mask(&[1, 2, 5]);
+    pencil_notes.possibilities[1][3] = mask(&[1, 2, 6]);
+
+    // 1 can only go into the roof in row 1
+    for c in [1, 2, 4, 5, 6, 7, 8] {
+        pencil_notes.remove_possibility(1, c, 1);
+    }
+
+    let uniqueness = pencil_notes.handle_uniqueness(UniquenessKind::UniqueRectangle4).expect("found the rectangle");
+
+    assert_eq!(uniqueness.eliminations, vec![(1, 0, 2), (1, 3, 2)]);
+    assert_eq!(pencil_notes.get_possibilities(1, 0), Candidates::from_numbers(&[1, 5]));
+}
+
+
+#[test]
+fn test_bug_plus_one() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    for r in 0..9 {
+        for c in 0..9 {
+            pencil_notes.possibilities[r][c] = 1 << ((r + c) % 9);
+        }
+    }
+    pencil_notes.possibilities[0][0] = mask(&[1, 2]);
+    pencil_notes.possibilities[0][3] = mask(&[1, 2]);
+    pencil_notes.possibilities[1][0] = mask(&[1, 2]);
+    pencil_notes.possibilities[1][3] = mask(&[1, 2, 3]);
+
+    let uniqueness = pencil_notes.find_uniqueness(UniquenessKind::BugPlusOne).expect("found the bug");
+
+    assert_eq!(uniqueness.cells, vec![(1, 3)]);
+    assert_eq!(uniqueness.placement, Some((1, 3, 3)));
+
+    // a second trivalue cell breaks the pattern
+    pencil_notes.possibilities[0][0] = mask(&[1, 2, 4]);
+    assert_eq!(pencil_notes.find_uniqueness(UniquenessKind::BugPlusOne), None);
+}
+
+
+#[test]
+fn test_uniqueness_is_opt_in() {
+
+    // a generated puzzle where a unique rectangle comes up before any harder technique
+    let sudoku = Sudoku::<9, 9>::new([
+         [9, 0, 5, 0, 7, 0, 2, 0, 0]
+        ,[0, 0, 0, 9, 0, 0, 0, 0, 0]
+        ,[0, 0, 0, 8, 0, 2, 7, 0, 4]
+        ,[7, 0, 4, 5, 0, 0, 0, 0, 0]
+        ,[0, 0, 0, 3, 0, 1, 0, 0, 8]
+        ,[0, 0, 9, 0, 0, 0, 0, 0, 3]
+        ,[1, 0, 0, 0, 2, 0, 0, 0, 0]
+        ,[5, 0, 0, 1, 0, 0, 3, 0, 0]
+        ,[0, 9, 0, 0, 0, 0, 5, 0, 1]]);
+
+    let is_unique_rectangle = |technique: Technique| matches!(technique,
+        Technique::UniqueRectangle1 | Technique::UniqueRectangle2 | Technique::UniqueRectangle3 | Technique::UniqueRectangle4);
+
+    let mut solver = LogicalSolver::new(&sudoku);
+    assert!(!solver.assume_uniqueness);
+
+    let without = solver.solve();
+    assert!(!without.steps.iter().any(|step| is_unique_rectangle(step.technique)));
+
+    let mut solver = LogicalSolver::new(&sudoku);
+    solver.assume_uniqueness = true;
+
+    let with = solver.solve();
+    assert!(with.steps.iter().any(|step| is_unique_rectangle(step.technique)));
+    assert_eq!(with.outcome, LogicalOutcome::Solved);
+    assert_eq!(Some(with.sudoku), sudoku.solve());
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use sudoku::sudoku_pencil_notes::PencilNotes;
+use sudoku::sudoku_pencil_notes_wings::WingKind;
+
+use common::mask;
+
+
+#[test]
+fn test_xy_wing() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    pencil_notes.possibilities[0][0] = mask(&[1, 2]);
+    pencil_notes.possibilities[0][5] = mask(&[1, 3]);
+    pencil_notes.possibilities[4][0] = mask(&[2, 3]);
+
+    let wing = pencil_notes.handle_wing(WingKind::XYWing).expect("found the xy-wing");
+
+    assert_eq!(wing.pivot, Some((0, 0)));
+    assert_eq!(wing.pincers, [(0, 5), (4, 0)]);
+    assert_eq!(wing.number, 3);
+    assert_eq!(wing.eliminations, vec![(4, 5, 3)]);
+
+    assert!(!pencil_notes.has_possibility(4, 5, 3));
+    assert!(pencil_notes.has_possibility(0, 1, 3));
+
+    assert_eq!(pencil_notes.find_wing(WingKind::XYWing), None);
+}
+
+
+#[test]
+fn test_xyz_wing() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    pencil_notes.possibilities[0][0] = mask(&[1, 2, 3]);
+    pencil_notes.possibilities[0][4] = mask(&[1, 3]);
+    pencil_notes.possibilities[1][1] = mask(&[2, 3]);
+
+    assert_eq!(pencil_notes.find_wing(WingKind::XYWing), None);
+
+    let wing = pencil_notes.handle_wing(WingKind::XYZWing).expect("found the xyz-wing");
+
+    assert_eq!(wing.pivot, Some((0, 0)));
+    assert_eq!(wing.number, 3);
+    assert_eq!(wing.eliminations, vec![(0, 1, 3), (0, 2, 3)]);
+
+    // cells seeing only the pincers keep the number
+    assert!(pencil_notes.has_possibility(1, 4, 3));
+}
+
+
+#[test]
+fn test_w_wing() {
+
+    let mut pencil_notes = PencilNotes::<9, 9>::new();
+    pencil_notes.possibilities[0][0] = mask(&[4, 7]);
+    pencil_notes.possibilities[5][8] = mask(&[4, 7]);
+
+    // 4 can only go to (3, 0) and (3, 7) in row 3
+    for c in 1..9 {
+        if c != 7 {
+            pencil_notes.remove_possibility(3, c, 4);
+        }
+    }
+
+    let wing = pencil_notes.handle_wing(WingKind::WWing).expect("found the w-wing");
+
+    assert_eq!(wing.pivot, None);
+    assert_eq!(wing.pincers, [(0, 0), (5, 8)]);
+    assert_eq!(wing.strong_link, Some([(3, 0), (3, 7)]));
+    assert_eq!(wing.number, 7);
+    assert_eq!(wing.eliminations, vec![(0, 8, 7), (5, 0, 7)]);
+
+    assert!(!pencil_notes.has_possibility(0, 8, 7));
+    assert!(!pencil_notes.has_possibility(5, 0, 7));
+}